@@ -8,12 +8,19 @@ use Colour::White as W;
 use Colour::Yellow as Y;
 
 fn main() {
-    // Old cube
+    // Old cube, in its own colour scheme
     // Start with solved cube, yellow front, white bottom.
     // Moves: R
-    /*
+    let old_scheme = ColourScheme {
+        top: B,
+        left: R,
+        front: Y,
+        right: G,
+        back: O,
+        bottom: W,
+    };
     #[rustfmt::skip]
-    let cube = Cube::make_cube([
+    let cube0 = Cube::make_cube([
                   /*Top*/
                   B, B, Y,
                   B, B, Y,
@@ -27,7 +34,9 @@ fn main() {
                   W, W, O,
                   W, W, O,
     ]);
-    */
+    let moves = solve_with_scheme(cube0, old_scheme);
+    println!("Old cube moves: {}", notation::format(&moves));
+
     // New cube
     // Start with solved cube, blue front, white bottom.
     // Moves: R
@@ -67,18 +76,160 @@ fn main() {
 
     // New cube
     // Start with solved cube, blue front, white bottom.
-    #[rustfmt::skip]
     let cube3 = {
         let mut c = Cube::SOLVED;
-        c.make_move(Move::R);
-        c.make_move(Move::FP);
-        c.make_move(Move::U);
-        c.make_move(Move::F);
-        c.make_move(Move::RP);
+        for m in notation::parse("R F' U F R'").unwrap() {
+            c.make_move(m);
+        }
+        c
+    };
+
+    // The same cube, held with a different face to the front.
+    let cube4 = {
+        let mut c = cube3.clone();
+        for m in notation::parse("x y'").unwrap() {
+            c.make_move(m);
+        }
+        c
+    };
+
+    // A random state, the same one every run.
+    let scramble = scramble::random_state(2024);
+    println!("Random-state scramble: {}", scramble);
+    println!(
+        "Random-move scramble: {}",
+        scramble::random_moves(2024, 25, &scramble::ALL_FACES)
+    );
+    println!(
+        "Random-move <R, U> scramble: {}",
+        scramble::random_moves(2024, 25, &[Move::R, Move::U])
+    );
+    let cube5 = {
+        let mut c = Cube::SOLVED;
+        for m in notation::parse(&scramble).unwrap() {
+            c.make_move(m);
+        }
         c
     };
 
-    let moves = solve(cube3);
-    println!("Moves: {:#?}", moves);
-    println!("Number of moves: {}", moves.len());
+    for cube in [cube1, cube2, cube3, cube4, cube5] {
+        let cross = cross::solve_colour_neutral(&cube, &Colour::ALL);
+        println!(
+            "Cheapest cross: {:?}, {} {}",
+            cross.colour,
+            notation::format(&cross.rotation),
+            notation::format(&cross.moves)
+        );
+        for plan in cross::plan_pairs(&cube, &[cross.colour], 1, 8) {
+            println!(
+                "X-cross {:?} {:?}: {} {}",
+                plan.colour,
+                plan.slots,
+                notation::format(&plan.rotation),
+                notation::format(&plan.moves)
+            );
+        }
+
+        let moves = solve_with(cube.clone(), Method::TwoPhase).unwrap();
+        println!("Two-phase moves: {}", notation::format(&moves));
+        let moves = solve_with_metric(cube.clone(), Method::TwoPhase, metric::Metric::Qtm).unwrap();
+        println!(
+            "Two-phase moves ({} QTM): {}",
+            metric::qtm(&moves),
+            notation::format(&moves)
+        );
+
+        for stage in thistlethwaite::solve(&cube) {
+            println!(
+                "Thistlethwaite {}: {}",
+                stage.name,
+                notation::format(&stage.moves)
+            );
+        }
+
+        for stage in cfop::solve(&cube) {
+            println!(
+                "CFOP {} ({} HTM): {}",
+                stage.name,
+                metric::htm(&stage.moves),
+                notation::format(&stage.moves)
+            );
+        }
+
+        for stage in roux::solve(&cube) {
+            println!(
+                "Roux {} ({} HTM): {}",
+                stage.name,
+                metric::htm(&stage.moves),
+                notation::format(&stage.moves)
+            );
+        }
+
+        println!("Misoriented edges: {:?}", cube.misoriented_edges().unwrap());
+        for stage in zz::solve(&cube) {
+            println!(
+                "ZZ {} ({} HTM): {}",
+                stage.name,
+                metric::htm(&stage.moves),
+                notation::format(&stage.moves)
+            );
+        }
+        for stage in petrus::solve(&cube).unwrap() {
+            println!(
+                "Petrus {} ({} HTM): {}",
+                stage.name,
+                metric::htm(&stage.moves),
+                notation::format(&stage.moves)
+            );
+        }
+        let bld = blindfold::old_pochmann(&cube, &blindfold::Lettering::SPEFFZ);
+        println!(
+            "Old Pochmann memo: edges {}, corners {}{}",
+            bld.memo.edge_pairs().join(" "),
+            bld.memo.corner_pairs().join(" "),
+            if bld.memo.parity { " (parity)" } else { "" }
+        );
+        let moves = bld.moves();
+        println!(
+            "Old Pochmann ({} HTM): {}",
+            metric::htm(&moves),
+            notation::format(&moves)
+        );
+        for (name, edges, corners) in [
+            (
+                "M2/R2",
+                blindfold::EdgeMethod::M2,
+                blindfold::CornerMethod::R2,
+            ),
+            (
+                "3-style",
+                blindfold::EdgeMethod::ThreeStyle,
+                blindfold::CornerMethod::ThreeStyle,
+            ),
+        ] {
+            let bld = blindfold::solve(&cube, edges, corners, &blindfold::Lettering::SPEFFZ);
+            for step in bld.steps {
+                let letters = if step.letters.is_empty() {
+                    "parity"
+                } else {
+                    &step.letters
+                };
+                println!(
+                    "{} {} ({} HTM): {}",
+                    name,
+                    letters,
+                    metric::htm(&step.moves),
+                    notation::format(&step.moves)
+                );
+            }
+        }
+
+        let moves = solve(cube);
+        println!("Moves: {:#?}", moves);
+        let counts: Vec<String> = metric::Metric::ALL
+            .iter()
+            .map(|metric| format!("{} {}", metric.count(&moves), metric.name()))
+            .collect();
+        println!("Number of moves: {}", counts.join(", "));
+    }
 }
@@ -0,0 +1,259 @@
+//! Algorithms: sequences of moves that can be inverted, mirrored, performed
+//! with the cube held another way, and simplified.
+//!
+//! Every move turns some layers clockwise about a direction, looking at the
+//! cube from that direction: a face turn about the face's direction, a slice
+//! turn about the direction of the face it turns like (left for `M`, down for
+//! `E` and front for `S`), and a rotation about right, up or front for `x`,
+//! `y` and `z`. Mirroring or rotating an algorithm moves those directions
+//! around, and a mirror also turns every clockwise turn anti-clockwise.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::metric::Metric;
+use crate::notation::{self, ParseMoveError, TURNS};
+use crate::Move;
+
+/// A sequence of moves.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Algorithm {
+    pub moves: Vec<Move>,
+}
+
+/// A plane through the middle of the cube, named after the slice in it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Plane {
+    /// Between the left and right faces, swapping them over.
+    M,
+    /// Between the top and bottom faces.
+    E,
+    /// Between the front and back faces.
+    S,
+}
+
+/// Which layers a kind of move turns.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Layers {
+    Face,
+    Slice,
+    Wide,
+    Rotation,
+}
+
+/// One way along an axis: 0 for right, 1 for up and 2 for front.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Direction {
+    axis: usize,
+    positive: bool,
+}
+
+impl Direction {
+    /// The direction after a clockwise quarter turn of the whole cube about
+    /// `axis`, looking from its positive end.
+    fn turned(self, axis: usize) -> Direction {
+        let (next, last) = ((axis + 1) % 3, (axis + 2) % 3);
+        if self.axis == last {
+            Direction { axis: next, ..self }
+        } else if self.axis == next {
+            Direction {
+                axis: last,
+                positive: !self.positive,
+            }
+        } else {
+            self
+        }
+    }
+}
+
+const fn direction(axis: usize, positive: bool) -> Direction {
+    Direction { axis, positive }
+}
+
+/// The layers and direction of each kind of move in [`TURNS`].
+#[rustfmt::skip]
+const KINDS: [(Layers, Direction); 18] = [
+    (Layers::Face, direction(2, true)),
+    (Layers::Face, direction(2, false)),
+    (Layers::Face, direction(1, true)),
+    (Layers::Face, direction(1, false)),
+    (Layers::Face, direction(0, false)),
+    (Layers::Face, direction(0, true)),
+    (Layers::Slice, direction(0, false)),
+    (Layers::Slice, direction(1, false)),
+    (Layers::Slice, direction(2, true)),
+    (Layers::Wide, direction(2, true)),
+    (Layers::Wide, direction(2, false)),
+    (Layers::Wide, direction(1, true)),
+    (Layers::Wide, direction(1, false)),
+    (Layers::Wide, direction(0, false)),
+    (Layers::Wide, direction(0, true)),
+    (Layers::Rotation, direction(0, true)),
+    (Layers::Rotation, direction(1, true)),
+    (Layers::Rotation, direction(2, true)),
+];
+
+/// The kind of a move, as an index into [`TURNS`] and [`KINDS`], and how many
+/// clockwise quarter turns it makes.
+fn quarter_turns(m: Move) -> (usize, u8) {
+    TURNS
+        .iter()
+        .enumerate()
+        .find_map(|(kind, (_, turns))| {
+            let turn = turns.iter().position(|&t| t == m)?;
+            Some((kind, [1, 3, 2][turn]))
+        })
+        .unwrap()
+}
+
+/// The move of `kind` making `quarter_turns` clockwise quarter turns, if it
+/// turns anything.
+fn from_quarter_turns(kind: usize, quarter_turns: u8) -> Option<Move> {
+    let turns = TURNS[kind].1;
+    match quarter_turns % 4 {
+        0 => None,
+        1 => Some(turns[0]),
+        2 => Some(turns[2]),
+        _ => Some(turns[1]),
+    }
+}
+
+/// The move that turns the same layers as `m` after their directions are
+/// moved by `map`, turning the other way if `reflect`.
+fn transform(m: Move, map: impl Fn(Direction) -> Direction, reflect: bool) -> Move {
+    let (kind, turns) = quarter_turns(m);
+    let (layers, from) = KINDS[kind];
+    let to = map(from);
+    let turns = if reflect { 4 - turns } else { turns };
+    // Faces and wide turns are named by their direction, but there is only one
+    // slice and one rotation about each axis.
+    let (kind, &(_, direction)) = KINDS
+        .iter()
+        .enumerate()
+        .find(|&(_, &(l, d))| {
+            l == layers
+                && match layers {
+                    Layers::Face | Layers::Wide => d == to,
+                    Layers::Slice | Layers::Rotation => d.axis == to.axis,
+                }
+        })
+        .unwrap();
+    let turns = if direction == to { turns } else { 4 - turns };
+    from_quarter_turns(kind, turns).unwrap()
+}
+
+impl Algorithm {
+    /// The number of moves in the algorithm, counted in `metric`.
+    pub fn count(&self, metric: Metric) -> usize {
+        metric.count(&self.moves)
+    }
+
+    /// The algorithm that undoes this one.
+    pub fn inverse(&self) -> Algorithm {
+        Algorithm {
+            moves: self.moves.iter().rev().map(Move::reverse).collect(),
+        }
+    }
+
+    /// The algorithm reflected in `plane`, e.g. the left-handed version of a
+    /// right-handed algorithm for [`Plane::M`].
+    pub fn mirror(&self, plane: Plane) -> Algorithm {
+        let axis = plane as usize;
+        let reflect = |d: Direction| Direction {
+            positive: d.positive != (d.axis == axis),
+            ..d
+        };
+        Algorithm {
+            moves: self
+                .moves
+                .iter()
+                .map(|&m| transform(m, reflect, true))
+                .collect(),
+        }
+    }
+
+    /// The algorithm that does what this one does when the cube is first
+    /// turned by `rotation` and turned back after, as if performed with the
+    /// cube held another way. `y` turns `R U R'` into `B U B'`.
+    ///
+    /// Panics if `rotation` isn't a rotation.
+    pub fn rotate(&self, rotation: Move) -> Algorithm {
+        let (kind, turns) = quarter_turns(rotation);
+        let (layers, axis) = KINDS[kind];
+        assert!(layers == Layers::Rotation, "{} isn't a rotation", rotation);
+        // A layer that is in some direction once the cube is turned was in the
+        // direction the turn undone takes it to.
+        let undo = |d: Direction| (0..4 - turns).fold(d, |d, _| d.turned(axis.axis));
+        Algorithm {
+            moves: self
+                .moves
+                .iter()
+                .map(|&m| transform(m, undo, false))
+                .collect(),
+        }
+    }
+
+    /// The algorithm with moves that undo each other cancelled, and moves of
+    /// the same kind merged, e.g. `U U U` becomes `U'`. Moves about the same
+    /// axis don't affect each other, so they are merged across each other
+    /// too, and `R L R'` becomes `L`.
+    pub fn simplify(&self) -> Algorithm {
+        // Each move as its kind and clockwise quarter turns.
+        let mut moves: Vec<(usize, u8)> = Vec::new();
+        for &m in &self.moves {
+            let (kind, turns) = quarter_turns(m);
+            let axis = KINDS[kind].1.axis;
+            let same_axis = moves
+                .iter()
+                .rev()
+                .take_while(|&&(k, _)| KINDS[k].1.axis == axis)
+                .count();
+            let start = moves.len() - same_axis;
+            match moves[start..].iter().position(|&(k, _)| k == kind) {
+                Some(i) => {
+                    let merged = (moves[start + i].1 + turns) % 4;
+                    if merged == 0 {
+                        moves.remove(start + i);
+                    } else {
+                        moves[start + i].1 = merged;
+                    }
+                }
+                None => moves.push((kind, turns)),
+            }
+        }
+        Algorithm {
+            moves: moves
+                .into_iter()
+                .filter_map(|(kind, turns)| from_quarter_turns(kind, turns))
+                .collect(),
+        }
+    }
+}
+
+impl From<Vec<Move>> for Algorithm {
+    fn from(moves: Vec<Move>) -> Self {
+        Algorithm { moves }
+    }
+}
+
+impl From<Algorithm> for Vec<Move> {
+    fn from(algorithm: Algorithm) -> Self {
+        algorithm.moves
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Algorithm {
+            moves: notation::parse(s)?,
+        })
+    }
+}
+
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", notation::format(&self.moves))
+    }
+}
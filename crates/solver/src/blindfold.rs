@@ -0,0 +1,865 @@
+//! Blindfolded solving: memorising the cube as letters, then solving it piece
+//! by piece without looking.
+//!
+//! Every sticker of a corner or edge is given a letter by a [`Lettering`],
+//! usually [`Lettering::SPEFFZ`]. The cube is memorised by tracing where the
+//! piece in the buffer position belongs, then where the piece displaced from
+//! there belongs, and so on, writing down the letter of each target sticker.
+//! When the buffer's own piece comes back to it before everything is solved,
+//! the trace breaks into a new cycle by shooting to the first unsolved piece.
+//! Pieces that are in place but flipped or twisted are solved the same way,
+//! shooting to one of their stickers and then another.
+//!
+//! The targets are solved with one of several methods:
+//!
+//! - Old Pochmann solves one target at a time. Setup moves bring the target to
+//!   the helper position, a fixed algorithm swaps the buffer and helper pieces,
+//!   and undoing the setup moves puts the target in place. Edges use the
+//!   T-perm with the buffer at UR and the helper at UL, and corners use a
+//!   Y-perm with the buffer at ULB and the helper at DFR.
+//! - M2 and R2 work the same way, M2 swapping the DF buffer with the UB helper
+//!   and R2 the UBR buffer with the DFR helper. Both also swap two other
+//!   pieces, so every second target on one of those is shot to where its
+//!   piece has gone instead. Targets the setup moves can't reach without
+//!   disturbing those pieces are cycled into the helper by a commutator.
+//! - 3-style solves two targets at a time with the shortest commutator found
+//!   that cycles the buffer's piece to the first target and the first target's
+//!   piece to the second, with the buffers at UF and UFR. Commutators are
+//!   `[A, B]`, that is `A B A' B'`, with up to three moves in `A` and two in
+//!   `B`, and up to three setup moves before them that are undone after. They
+//!   are compared once simplified, so setup moves that cancel with the
+//!   commutator's don't count.
+//!
+//! Every step's moves are simplified with [`Algorithm::simplify`], so no step
+//! turns the same layer twice in a row.
+//!
+//! An odd number of edge targets comes with an odd number of corner targets,
+//! which no sequence of swaps of pairs can solve. With Old Pochmann for both,
+//! the T-perm's swap of two corners is then left behind after the edges, and a
+//! parity algorithm swaps them back along with the two edges the Y-perms leave
+//! swapped. With any other methods, the UB and UL edges and the UFR and UBR
+//! corners are memorised as if they were swapped, and the same parity
+//! algorithm at the end swaps them.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::algorithm::Algorithm;
+use crate::cubie::{Corner, CubieCube, Edge, CORNER_FACELETS, EDGE_FACELETS};
+use crate::{notation, Colour, Cube, Face, FaceName, Move};
+
+/// The letter of every corner and edge sticker, four to each face. The faces
+/// go top, left, front, right, back, bottom, and each face's letters go
+/// clockwise from the top left, looking at the face with the top face up, or
+/// for the top and bottom faces, with the back and front face up respectively.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lettering {
+    pub corners: [char; 24],
+    pub edges: [char; 24],
+}
+
+impl Lettering {
+    /// The Speffz scheme, lettering both the corners and the edges `A` to `X`.
+    pub const SPEFFZ: Lettering = Lettering {
+        corners: SPEFFZ_LETTERS,
+        edges: SPEFFZ_LETTERS,
+    };
+}
+
+impl Default for Lettering {
+    fn default() -> Self {
+        Lettering::SPEFFZ
+    }
+}
+
+#[rustfmt::skip]
+const SPEFFZ_LETTERS: [char; 24] = [
+    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L',
+    'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X',
+];
+
+/// How the edges are solved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeMethod {
+    OldPochmann,
+    M2,
+    ThreeStyle,
+}
+
+/// How the corners are solved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CornerMethod {
+    OldPochmann,
+    R2,
+    ThreeStyle,
+}
+
+/// The letters to memorise for a blindfolded solve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Memo {
+    /// The edge targets, in the order they're solved.
+    pub edges: Vec<char>,
+    /// The corner targets, in the order they're solved.
+    pub corners: Vec<char>,
+    /// Whether the parity algorithm is needed.
+    pub parity: bool,
+}
+
+impl Memo {
+    /// The edge targets in pairs, as they are usually memorised. The last
+    /// pair is a single letter if there is an odd number of targets.
+    pub fn edge_pairs(&self) -> Vec<String> {
+        pairs(&self.edges)
+    }
+
+    /// The corner targets in pairs, like [`Memo::edge_pairs`].
+    pub fn corner_pairs(&self) -> Vec<String> {
+        pairs(&self.corners)
+    }
+}
+
+fn pairs(letters: &[char]) -> Vec<String> {
+    letters
+        .chunks(2)
+        .map(|pair| pair.iter().collect())
+        .collect()
+}
+
+/// The moves for one memo pair, or for the parity algorithm.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// The letters of the pair, a single letter for an odd one out, or empty
+    /// for the parity algorithm.
+    pub letters: String,
+    pub moves: Vec<Move>,
+}
+
+/// A blindfolded solve: the letters to memorise and the moves that solve the
+/// cube, grouped by memo pair so that each pair can be drilled on its own.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pub memo: Memo,
+    /// The edge pairs, then the corner pairs, with the parity algorithm
+    /// between them for Old Pochmann and after them otherwise.
+    pub steps: Vec<Step>,
+}
+
+impl Solution {
+    /// All the moves of the solve, simplified where one step's moves cancel
+    /// with the next's.
+    pub fn moves(&self) -> Vec<Move> {
+        let moves = self
+            .steps
+            .iter()
+            .flat_map(|step| step.moves.iter().copied());
+        simplified(moves.collect())
+    }
+}
+
+/// The indices of the stickers of each face in lettering order, for corners
+/// and for edges.
+const CORNER_INDICES: [usize; 4] = [0, 2, 8, 6];
+const EDGE_INDICES: [usize; 4] = [1, 5, 7, 3];
+
+/// The Old Pochmann algorithms, which swap the buffer and helper pieces along
+/// with two pieces of the other kind.
+const EDGE_SWAP: &str = "R U R' U' R' F R2 U' R' U' R U R' F'";
+const CORNER_SWAP: &str = "R U' R' U' R U R' F' R U R' U' R' F R";
+
+/// Swaps the UB and UL edges and the UFR and UBR corners.
+const PARITY: &str = "R U' R' U' R U R D R' U' R D' R' U2 R' U'";
+
+/// The most moves a setup of a target to a helper can take.
+const MAX_SETUP_LENGTH: usize = 4;
+
+/// The most moves in the `A` and the `B` of a commutator `[A, B]`, and in the
+/// setup moves before a commutator. Setups of the most moves are only tried
+/// when no shorter one works, since they are rarely needed and many to try.
+const MAX_INSERTION_LENGTH: usize = 3;
+const MAX_INTERCHANGE_LENGTH: usize = 2;
+const MAX_CONJUGATE_LENGTH: usize = 3;
+
+/// The moves that setups and commutators are made of, in order of preference,
+/// each kind of turn together. Commutators are made of the face and slice
+/// turns, which come first.
+#[rustfmt::skip]
+const MOVES: [Move; 45] = [
+    Move::U, Move::UP, Move::U2, Move::D, Move::DP, Move::D2,
+    Move::L, Move::LP, Move::L2, Move::R, Move::RP, Move::R2,
+    Move::F, Move::FP, Move::F2, Move::B, Move::BP, Move::B2,
+    Move::M, Move::MP, Move::M2, Move::E, Move::EP, Move::E2,
+    Move::S, Move::SP, Move::S2,
+    Move::Uw, Move::UwP, Move::Uw2, Move::Dw, Move::DwP, Move::Dw2,
+    Move::Lw, Move::LwP, Move::Lw2, Move::Rw, Move::RwP, Move::Rw2,
+    Move::Fw, Move::FwP, Move::Fw2, Move::Bw, Move::BwP, Move::Bw2,
+];
+const N_COMMUTATOR_MOVES: usize = 27;
+
+/// A sticker, numbered `face * 9 + index`.
+type Facelet = usize;
+
+/// Where some moves take the sticker at each facelet.
+type Perm = [u8; 54];
+
+const IDENTITY: Perm = {
+    let mut perm = [0; 54];
+    let mut f = 0;
+    while f < 54 {
+        perm[f] = f as u8;
+        f += 1;
+    }
+    perm
+};
+
+/// The moves of `p` followed by those of `q`.
+fn then(p: &Perm, q: &Perm) -> Perm {
+    p.map(|f| q[f as usize])
+}
+
+fn inverse(p: &Perm) -> Perm {
+    let mut result = [0; 54];
+    for (f, &to) in p.iter().enumerate() {
+        result[to as usize] = f as u8;
+    }
+    result
+}
+
+fn invert(moves: &[Move]) -> Vec<Move> {
+    moves.iter().rev().map(Move::reverse).collect()
+}
+
+/// The stickers a permutation moves and where it moves them, in order.
+fn moved(p: &Perm) -> Vec<(u8, u8)> {
+    (0..54)
+        .map(|f| (f, p[f as usize]))
+        .filter(|&(f, to)| f != to)
+        .collect()
+}
+
+/// Where each of [`MOVES`] takes each sticker, found by painting the digits of
+/// the sticker numbers in base 6 onto three cubes and reading them back after
+/// the move.
+fn move_perms() -> &'static [Perm; 45] {
+    static PERMS: OnceLock<[Perm; 45]> = OnceLock::new();
+    PERMS.get_or_init(|| {
+        MOVES.map(|m| {
+            let mut from = [0; 54];
+            let mut place = 1;
+            for _ in 0..3 {
+                let mut cube = Cube {
+                    faces: std::array::from_fn(|face| {
+                        Face(std::array::from_fn(|i| {
+                            Colour::ALL[(face * 9 + i) / place % 6]
+                        }))
+                    }),
+                };
+                cube.make_move(m);
+                let colours = cube.faces.iter().flat_map(|face| face.0);
+                for (f, colour) in colours.enumerate() {
+                    from[f] += place * Colour::ALL.iter().position(|&c| c == colour).unwrap();
+                }
+                place *= 6;
+            }
+            let mut to = [0; 54];
+            for (f, &from) in from.iter().enumerate() {
+                to[from] = f as u8;
+            }
+            to
+        })
+    })
+}
+
+fn perm_of(moves: &[Move]) -> Perm {
+    moves.iter().fold(IDENTITY, |perm, m| {
+        let m = MOVES.iter().position(|n| n == m).unwrap();
+        then(&perm, &move_perms()[m])
+    })
+}
+
+/// Every sequence of up to `max_length` of the first `n_moves` of [`MOVES`],
+/// without two turns of the same layer in a row, shortest first, with where it
+/// takes each sticker.
+fn sequences(n_moves: usize, max_length: usize) -> Vec<(Vec<usize>, Perm)> {
+    let mut sequences = vec![(Vec::new(), IDENTITY)];
+    let mut start = 0;
+    for _ in 0..max_length {
+        let end = sequences.len();
+        for i in start..end {
+            for m in 0..n_moves {
+                let (moves, perm) = &sequences[i];
+                if moves.last().is_some_and(|&prev: &usize| prev / 3 == m / 3) {
+                    continue;
+                }
+                let next = (
+                    [moves.as_slice(), &[m]].concat(),
+                    then(perm, &move_perms()[m]),
+                );
+                sequences.push(next);
+            }
+        }
+        start = end;
+    }
+    sequences
+}
+
+/// The stickers of one kind of piece, numbered as in [`Lettering`].
+struct Pieces {
+    /// The stickers of each position, in the order of [`CORNER_FACELETS`] or
+    /// [`EDGE_FACELETS`].
+    positions: Vec<Vec<usize>>,
+    /// The indices of the stickers on each face, in lettering order.
+    indices: [usize; 4],
+}
+
+impl Pieces {
+    fn new<const N: usize>(facelets: &[[(FaceName, usize); N]], indices: [usize; 4]) -> Pieces {
+        let number = |(face, index): (FaceName, usize)| {
+            face as usize * 4 + indices.iter().position(|&i| i == index).unwrap()
+        };
+        Pieces {
+            positions: facelets
+                .iter()
+                .map(|facelets| facelets.iter().map(|&f| number(f)).collect())
+                .collect(),
+            indices,
+        }
+    }
+
+    fn facelet(&self, sticker: usize) -> Facelet {
+        sticker / 4 * 9 + self.indices[sticker % 4]
+    }
+
+    /// The sticker at `facelet`, if it is one of this kind of piece.
+    fn sticker(&self, facelet: Facelet) -> Option<usize> {
+        let index = self.indices.iter().position(|&i| i == facelet % 9)?;
+        Some(facelet / 9 * 4 + index)
+    }
+
+    /// The position holding `sticker`.
+    fn position(&self, sticker: usize) -> usize {
+        self.positions
+            .iter()
+            .position(|stickers| stickers.contains(&sticker))
+            .unwrap()
+    }
+
+    /// The stickers of the position holding `sticker`, going around it from
+    /// `sticker`.
+    fn around(&self, sticker: usize) -> Vec<usize> {
+        let position = &self.positions[self.position(sticker)];
+        let start = position.iter().position(|&s| s == sticker).unwrap();
+        (0..position.len())
+            .map(|i| position[(start + i) % position.len()])
+            .collect()
+    }
+
+    /// The sticker at each sticker's place, given which piece is at each
+    /// position and how it is oriented.
+    fn stickers_at(&self, pieces: &[usize], orientations: &[u8]) -> Vec<usize> {
+        let mut at = vec![0; 24];
+        for (position, (&piece, &ori)) in pieces.iter().zip(orientations).enumerate() {
+            let (here, home) = (&self.positions[position], &self.positions[piece]);
+            let n = here.len();
+            for (j, &sticker) in here.iter().enumerate() {
+                at[sticker] = home[(j + n - ori as usize) % n];
+            }
+        }
+        at
+    }
+
+    /// Traces the cycles from `buffer`, given the sticker at each sticker's
+    /// place, giving the targets in order.
+    fn trace(&self, buffer: usize, mut at: Vec<usize>) -> Vec<usize> {
+        let buffer_stickers = self.around(buffer);
+        let mut targets = Vec::new();
+        loop {
+            let target = if buffer_stickers.contains(&at[buffer]) {
+                // The buffer's own piece is home, so break into a new cycle.
+                let unsolved = |&s: &usize| !buffer_stickers.contains(&s) && at[s] != s;
+                match (0..at.len()).find(unsolved) {
+                    Some(target) => target,
+                    None => break,
+                }
+            } else {
+                at[buffer]
+            };
+            for (b, t) in buffer_stickers.iter().zip(self.around(target)) {
+                at.swap(*b, t);
+            }
+            targets.push(target);
+        }
+        targets
+    }
+
+    /// Swaps the pieces at `buffer` and `target`, moving each sticker of one
+    /// to the matching sticker of the other, which is what solving `target`
+    /// does.
+    fn swap(&self, buffer: usize, target: usize) -> Perm {
+        let mut perm = IDENTITY;
+        for (b, t) in self.around(buffer).into_iter().zip(self.around(target)) {
+            let (b, t) = (self.facelet(b), self.facelet(t));
+            perm[b] = t as u8;
+            perm[t] = b as u8;
+        }
+        perm
+    }
+
+    /// Whether `p` cycles three of these pieces around, leaving everything
+    /// else alone.
+    fn is_three_cycle(&self, p: &Perm) -> bool {
+        let moved = moved(p);
+        let position = |f: u8| self.sticker(f as usize).map(|s| self.position(s));
+        moved.len() == 3 * self.positions[0].len()
+            && moved.iter().all(|&(f, to)| {
+                let (from, to) = (position(f), position(to));
+                from.is_some() && to.is_some() && from != to
+            })
+    }
+}
+
+/// Finds a shortest setup for each of `targets` that brings it to `helper`
+/// without moving any of the `fixed` stickers, if there is one of at most
+/// [`MAX_SETUP_LENGTH`] moves.
+fn find_setups(helper: Facelet, fixed: &[Facelet], targets: &[Facelet]) -> Vec<Option<Vec<Move>>> {
+    struct Search<'a> {
+        helper: Facelet,
+        fixed: &'a [Facelet],
+        path: Vec<usize>,
+        setups: Vec<Option<Vec<Move>>>,
+        /// The number of targets still without a setup.
+        missing: usize,
+    }
+
+    impl Search<'_> {
+        /// Tries every setup of exactly `depth` more moves, given where the
+        /// setup so far takes the fixed stickers followed by the targets.
+        fn search(&mut self, to: &[Facelet], depth: usize) {
+            if self.missing == 0 {
+                return;
+            }
+            if depth == 0 {
+                let (fixed, targets) = to.split_at(self.fixed.len());
+                if fixed != self.fixed {
+                    return;
+                }
+                if let Some(i) = targets.iter().position(|&f| f == self.helper) {
+                    if self.setups[i].is_none() {
+                        let setup = self.path.iter().map(|&m| MOVES[m]).collect();
+                        self.setups[i] = Some(setup);
+                        self.missing -= 1;
+                    }
+                }
+                return;
+            }
+            for m in 0..MOVES.len() {
+                if self.path.last().is_some_and(|&prev| prev / 3 == m / 3) {
+                    continue;
+                }
+                self.path.push(m);
+                let perm = &move_perms()[m];
+                let next: Vec<Facelet> = to.iter().map(|&f| perm[f] as usize).collect();
+                self.search(&next, depth - 1);
+                self.path.pop();
+            }
+        }
+    }
+
+    let mut search = Search {
+        helper,
+        fixed,
+        path: Vec::new(),
+        setups: vec![None; targets.len()],
+        missing: targets.len(),
+    };
+    let start = [fixed, targets].concat();
+    for depth in 0..=MAX_SETUP_LENGTH {
+        search.search(&start, depth);
+    }
+    search.setups
+}
+
+/// Commutators by the stickers they move and where, in order.
+type Commutators = HashMap<Vec<(u8, u8)>, Vec<Move>>;
+
+/// The shortest commutator `[A, B]` found for each three-cycle of corners or
+/// of edges, simplified.
+fn commutators() -> &'static Commutators {
+    static COMMUTATORS: OnceLock<Commutators> = OnceLock::new();
+    COMMUTATORS.get_or_init(|| {
+        let t = tables();
+        let interchanges: Vec<(Vec<Move>, Perm, Perm)> =
+            sequences(N_COMMUTATOR_MOVES, MAX_INTERCHANGE_LENGTH)[1..]
+                .iter()
+                .map(|(b, perm)| (b.iter().map(|&m| MOVES[m]).collect(), *perm, inverse(perm)))
+                .collect();
+        let mut commutators = Commutators::new();
+        for (a, a_perm) in &sequences(N_COMMUTATOR_MOVES, MAX_INSERTION_LENGTH)[1..] {
+            let a: Vec<Move> = a.iter().map(|&m| MOVES[m]).collect();
+            let a_inverse = inverse(a_perm);
+            for (b, b_perm, b_inverse) in &interchanges {
+                let perm = then(&then(&then(a_perm, b_perm), &a_inverse), b_inverse);
+                // Most commutators move far more stickers than a three-cycle,
+                // so count them before looking closer.
+                let n_moved = (0..54).filter(|&f| perm[f] != f as u8).count();
+                if n_moved > 9 || !t.corners.is_three_cycle(&perm) && !t.edges.is_three_cycle(&perm)
+                {
+                    continue;
+                }
+                let moves = simplified([&a[..], b, &invert(&a), &invert(b)].concat());
+                // Simplifying can make a later commutator shorter, so only the
+                // first of the fewest moves is kept.
+                match commutators.entry(moved(&perm)) {
+                    Entry::Occupied(mut shortest) => {
+                        if moves.len() < shortest.get().len() {
+                            shortest.insert(moves);
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(moves);
+                    }
+                }
+            }
+        }
+        commutators
+    })
+}
+
+/// Every setup of up to [`MAX_CONJUGATE_LENGTH`] moves, with where it takes
+/// each sticker.
+fn conjugates() -> &'static [(Vec<usize>, Perm)] {
+    static CONJUGATES: OnceLock<Vec<(Vec<usize>, Perm)>> = OnceLock::new();
+    CONJUGATES.get_or_init(|| sequences(MOVES.len(), MAX_CONJUGATE_LENGTH))
+}
+
+/// The shortest commutator found, with setup moves, that makes the
+/// three-cycle `cycle`, simplified.
+fn three_cycle(cycle: &Perm) -> Vec<Move> {
+    let moved = moved(cycle);
+    let shortest = |conjugates: &'static [(Vec<usize>, Perm)]| {
+        conjugates
+            .iter()
+            .filter_map(|(setup, perm)| {
+                // After the setup, the commutator cycles the stickers from
+                // where the setup has taken them.
+                let mut key: Vec<(u8, u8)> = moved
+                    .iter()
+                    .map(|&(f, to)| (perm[f as usize], perm[to as usize]))
+                    .collect();
+                key.sort_unstable();
+                let commutator = commutators().get(&key)?;
+                let setup: Vec<Move> = setup.iter().map(|&m| MOVES[m]).collect();
+                Some(simplified(
+                    [&setup[..], commutator, &invert(&setup)].concat(),
+                ))
+            })
+            .min_by_key(Vec::len)
+    };
+    let conjugates = conjugates();
+    let longest = conjugates.partition_point(|(setup, _)| setup.len() < MAX_CONJUGATE_LENGTH);
+    shortest(&conjugates[..longest])
+        .or_else(|| shortest(&conjugates[longest..]))
+        .expect("every three-cycle has a commutator")
+}
+
+fn simplified(moves: Vec<Move>) -> Vec<Move> {
+    Algorithm::from(moves).simplify().moves
+}
+
+/// A method that solves one target at a time: Old Pochmann, M2 or R2.
+struct Shuttle {
+    buffer: usize,
+    /// Where the algorithm's side effect, what it does besides swapping the
+    /// buffer and the helper, takes each sticker.
+    side: Vec<usize>,
+    /// The moves that solve each target, while the side effect hasn't been
+    /// made: the setup, the algorithm and the setup undone, or if there is no
+    /// setup, a commutator and the algorithm. Empty for the buffer's stickers.
+    solves: Vec<Vec<Move>>,
+}
+
+impl Shuttle {
+    /// Makes the tables for a method whose algorithm `alg` swaps the `buffer`
+    /// and `helper` pieces, and has a side effect that leaves both alone.
+    fn new(pieces: &Pieces, buffer: usize, helper: usize, alg: &str) -> Shuttle {
+        let alg = notation::parse(alg).unwrap();
+        let alg_perm = perm_of(&alg);
+        let side_effect = then(&pieces.swap(buffer, helper), &alg_perm);
+        let buffer_stickers = pieces.around(buffer);
+
+        // The setups mustn't move the buffer or anything the side effect
+        // moves, so that they still work once the side effect has been made.
+        let mut fixed: Vec<Facelet> = buffer_stickers.iter().map(|&s| pieces.facelet(s)).collect();
+        fixed.extend(moved(&side_effect).into_iter().map(|(f, _)| f as usize));
+        let targets: Vec<usize> = (0..24)
+            .filter(|&s| !fixed.contains(&pieces.facelet(s)))
+            .collect();
+        let target_facelets: Vec<Facelet> = targets.iter().map(|&s| pieces.facelet(s)).collect();
+        let mut setups = vec![None; 24];
+        let found = find_setups(pieces.facelet(helper), &fixed, &target_facelets);
+        for (s, setup) in targets.into_iter().zip(found) {
+            setups[s] = setup;
+        }
+
+        let solves = (0..24)
+            .map(|s| match &setups[s] {
+                _ if buffer_stickers.contains(&s) => Vec::new(),
+                Some(setup) => simplified([&setup[..], &alg, &invert(setup)].concat()),
+                // Solve the target and then the helper, leaving the buffer's
+                // piece at the helper for the algorithm to swap back.
+                None => simplified([two_targets(pieces, buffer, s, helper), alg.clone()].concat()),
+            })
+            .collect();
+        let side = (0..24)
+            .map(|s| {
+                let facelet = side_effect[pieces.facelet(s)];
+                pieces.sticker(facelet as usize).unwrap()
+            })
+            .collect();
+        Shuttle {
+            buffer,
+            side,
+            solves,
+        }
+    }
+
+    /// The moves that solve each of the targets in turn.
+    fn execute(&self, targets: &[usize]) -> Vec<Vec<Move>> {
+        targets
+            .iter()
+            .enumerate()
+            .map(|(i, &target)| {
+                // After an odd number of algorithms, the side effect has moved
+                // the target's piece.
+                let target = if i % 2 == 1 {
+                    self.side[target]
+                } else {
+                    target
+                };
+                self.solves[target].clone()
+            })
+            .collect()
+    }
+}
+
+/// The moves that solve `first` and then `second` from `buffer` with
+/// commutators: a single one if the targets are on different pieces, or two
+/// through some other piece if they flip or twist a piece in place.
+fn two_targets(pieces: &Pieces, buffer: usize, first: usize, second: usize) -> Vec<Move> {
+    let cycle = |first, second| {
+        three_cycle(&then(
+            &pieces.swap(buffer, first),
+            &pieces.swap(buffer, second),
+        ))
+    };
+    let position = pieces.position(first);
+    if position != pieces.position(second) {
+        return cycle(first, second);
+    }
+    let buffer_position = pieces.position(buffer);
+    (0..24)
+        .filter(|&s| ![buffer_position, position].contains(&pieces.position(s)))
+        .map(|s| simplified([cycle(first, s), cycle(s, second)].concat()))
+        .min_by_key(Vec::len)
+        .unwrap()
+}
+
+/// The moves that solve each pair of targets with 3-style, given an even
+/// number of targets.
+fn execute_three_style(pieces: &Pieces, buffer: usize, targets: &[usize]) -> Vec<Vec<Move>> {
+    targets
+        .chunks(2)
+        .map(|pair| two_targets(pieces, buffer, pair[0], pair[1]))
+        .collect()
+}
+
+struct Tables {
+    edges: Pieces,
+    corners: Pieces,
+    /// The state the parity algorithm leaves a solved cube in.
+    parity: CubieCube,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let mut parity = CubieCube::SOLVED;
+        for m in notation::parse(PARITY).unwrap() {
+            parity.make_move(m);
+        }
+        Tables {
+            edges: Pieces::new(&EDGE_FACELETS, EDGE_INDICES),
+            corners: Pieces::new(&CORNER_FACELETS, CORNER_INDICES),
+            parity,
+        }
+    })
+}
+
+fn edge_sticker(edge: Edge) -> usize {
+    tables().edges.positions[edge as usize][0]
+}
+
+fn corner_sticker(corner: Corner) -> usize {
+    tables().corners.positions[corner as usize][0]
+}
+
+/// The methods that solve one target at a time, whose tables are each made
+/// the first time they're used.
+#[derive(Clone, Copy)]
+enum ShuttleMethod {
+    OldPochmannEdges,
+    M2,
+    OldPochmannCorners,
+    R2,
+}
+
+fn shuttle(method: ShuttleMethod) -> &'static Shuttle {
+    static SHUTTLES: [OnceLock<Shuttle>; 4] = [const { OnceLock::new() }; 4];
+    SHUTTLES[method as usize].get_or_init(|| {
+        let t = tables();
+        match method {
+            ShuttleMethod::OldPochmannEdges => Shuttle::new(
+                &t.edges,
+                edge_sticker(Edge::Ur),
+                edge_sticker(Edge::Ul),
+                EDGE_SWAP,
+            ),
+            ShuttleMethod::M2 => Shuttle::new(
+                &t.edges,
+                edge_sticker(Edge::Df),
+                edge_sticker(Edge::Ub),
+                "M2",
+            ),
+            ShuttleMethod::OldPochmannCorners => Shuttle::new(
+                &t.corners,
+                corner_sticker(Corner::Ulb),
+                t.corners.positions[Corner::Dfr as usize][2],
+                CORNER_SWAP,
+            ),
+            ShuttleMethod::R2 => Shuttle::new(
+                &t.corners,
+                corner_sticker(Corner::Ubr),
+                corner_sticker(Corner::Dfr),
+                "R2",
+            ),
+        }
+    })
+}
+
+/// Traces one kind of piece, given the sticker at each sticker's place, and
+/// works out the moves that solve each pair of targets, with `shuttle` or
+/// otherwise with 3-style from `three_style_buffer`.
+fn solve_pieces(
+    pieces: &Pieces,
+    at: Vec<usize>,
+    shuttle: Option<&Shuttle>,
+    three_style_buffer: usize,
+) -> (Vec<usize>, Vec<Vec<Move>>) {
+    match shuttle {
+        Some(shuttle) => {
+            let targets = pieces.trace(shuttle.buffer, at);
+            let moves = shuttle.execute(&targets);
+            let pairs = moves.chunks(2).map(|pair| simplified(pair.concat()));
+            (targets, pairs.collect())
+        }
+        None => {
+            let targets = pieces.trace(three_style_buffer, at);
+            let moves = execute_three_style(pieces, three_style_buffer, &targets);
+            (targets, moves)
+        }
+    }
+}
+
+/// Memorises the cube and works out the moves to solve it blindfolded with the
+/// given methods, lettering the stickers with `lettering`. The cube is solved
+/// as it is held, relative to its centres.
+///
+/// Panics if the cube can't be solved.
+pub fn solve(
+    cube: &Cube,
+    edge_method: EdgeMethod,
+    corner_method: CornerMethod,
+    lettering: &Lettering,
+) -> Solution {
+    let t = tables();
+    let mut cube = CubieCube::from_solvable_cube(cube);
+    let edges_at = |cube: &CubieCube| t.edges.stickers_at(&cube.ep.map(|e| e as usize), &cube.eo);
+    let corners_at = |cube: &CubieCube| {
+        t.corners
+            .stickers_at(&cube.cp.map(|c| c as usize), &cube.co)
+    };
+
+    let parity = t.edges.trace(edge_sticker(Edge::Uf), edges_at(&cube)).len() % 2 == 1;
+    let old_pochmann =
+        edge_method == EdgeMethod::OldPochmann && corner_method == CornerMethod::OldPochmann;
+    if parity && !old_pochmann {
+        // Memorise the cube as if the parity algorithm had been made first,
+        // since making it last undoes that.
+        let mut swapped = t.parity.clone();
+        swapped.multiply(&cube);
+        cube = swapped;
+    }
+
+    let edge_shuttle = match edge_method {
+        EdgeMethod::OldPochmann => Some(shuttle(ShuttleMethod::OldPochmannEdges)),
+        EdgeMethod::M2 => Some(shuttle(ShuttleMethod::M2)),
+        EdgeMethod::ThreeStyle => None,
+    };
+    let corner_shuttle = match corner_method {
+        CornerMethod::OldPochmann => Some(shuttle(ShuttleMethod::OldPochmannCorners)),
+        CornerMethod::R2 => Some(shuttle(ShuttleMethod::R2)),
+        CornerMethod::ThreeStyle => None,
+    };
+    let (edges, edge_moves) = solve_pieces(
+        &t.edges,
+        edges_at(&cube),
+        edge_shuttle,
+        edge_sticker(Edge::Uf),
+    );
+    let (corners, corner_moves) = solve_pieces(
+        &t.corners,
+        corners_at(&cube),
+        corner_shuttle,
+        corner_sticker(Corner::Urf),
+    );
+
+    let memo = Memo {
+        edges: edges.iter().map(|&s| lettering.edges[s]).collect(),
+        corners: corners.iter().map(|&s| lettering.corners[s]).collect(),
+        parity,
+    };
+    let steps = |pairs: Vec<String>, moves: Vec<Vec<Move>>| {
+        pairs
+            .into_iter()
+            .zip(moves)
+            .map(|(letters, moves)| Step { letters, moves })
+    };
+    let parity_step = Step {
+        letters: String::new(),
+        moves: notation::parse(PARITY).unwrap(),
+    };
+    let mut solution = Solution {
+        steps: steps(memo.edge_pairs(), edge_moves).collect(),
+        memo,
+    };
+    if parity && old_pochmann {
+        solution.steps.push(parity_step.clone());
+    }
+    let corner_steps = steps(solution.memo.corner_pairs(), corner_moves);
+    solution.steps.extend(corner_steps);
+    if parity && !old_pochmann {
+        solution.steps.push(parity_step);
+    }
+    solution
+}
+
+/// Memorises the cube and works out the moves to solve it with Old Pochmann
+/// for both the edges and the corners, like [`solve`].
+pub fn old_pochmann(cube: &Cube, lettering: &Lettering) -> Solution {
+    solve(
+        cube,
+        EdgeMethod::OldPochmann,
+        CornerMethod::OldPochmann,
+        lettering,
+    )
+}
@@ -0,0 +1,224 @@
+//! The CFOP method: cross, first two layers (F2L), orientation of the last
+//! layer (OLL) and permutation of the last layer (PLL).
+//!
+//! The cross is solved in as few moves as possible, and each F2L pair is found
+//! by a search for the cheapest of the remaining pairs to solve without
+//! disturbing the cross or the pairs already in place. The last layer is
+//! solved with a table of the standard 57 OLL and 21 PLL algorithms, each case
+//! recognised by trying the algorithms from each turn of the top layer until
+//! one works.
+//!
+//! Like the other solvers, this works relative to the centres, so the cross is
+//! built on whichever colour is on the bottom.
+
+use std::sync::OnceLock;
+
+use crate::cross::{self, Slot};
+use crate::cubie::CubieCube;
+use crate::metric::Metric;
+use crate::{notation, Cube, Move, Stage};
+
+/// The OLL algorithms, in the usual order, so that case `n` is at index
+/// `n - 1`.
+#[rustfmt::skip]
+pub(crate) const OLL: [&str; 57] = [
+    "R U2 R2 F R F' U2 R' F R F'",
+    "F R U R' U' F' f R U R' U' f'",
+    "f R U R' U' f' U' F R U R' U' F'",
+    "f R U R' U' f' U F R U R' U' F'",
+    "r' U2 R U R' U r",
+    "r U2 R' U' R U' r'",
+    "r U R' U R U2 r'",
+    "r' U' R U' R' U2 r",
+    "R U R' U' R' F R2 U R' U' F'",
+    "R U R' U R' F R F' R U2 R'",
+    "r U R' U R' F R F' R U2 r'",
+    "M' R' U' R U' R' U2 R U' R r'",
+    "F U R U' R2 F' R U R U' R'",
+    "R' F R U R' F' R F U' F'",
+    "r' U' r R' U' R U r' U r",
+    "r U r' R U R' U' r U' r'",
+    "R U R' U R' F R F' U2 R' F R F'",
+    "r U R' U R U2 r2 U' R U' R' U2 r",
+    "r' R U R U R' U' M' R' F R F'",
+    "r U R' U' M2 U R U' R' U' M'",
+    "R U2 R' U' R U R' U' R U' R'",
+    "R U2 R2 U' R2 U' R2 U2 R",
+    "R2 D' R U2 R' D R U2 R",
+    "r U R' U' r' F R F'",
+    "F' r U R' U' r' F R",
+    "R U2 R' U' R U' R'",
+    "R U R' U R U2 R'",
+    "r U R' U' M U R U' R'",
+    "R U R' U' R U' R' F' U' F R U R'",
+    "F R' F R2 U' R' U' R U R' F2",
+    "R' U' F U R U' R' F' R",
+    "L U F' U' L' U L F L'",
+    "R U R' U' R' F R F'",
+    "R U R2 U' R' F R U R U' F'",
+    "R U2 R2 F R F' R U2 R'",
+    "L' U' L U' L' U L U L F' L' F",
+    "F R' F' R U R U' R'",
+    "R U R' U R U' R' U' R' F R F'",
+    "L F' L' U' L U F U' L'",
+    "R' F R U R' U' F' U R",
+    "R U R' U R U2 R' F R U R' U' F'",
+    "R' U' R U' R' U2 R F R U R' U' F'",
+    "F' U' L' U L F",
+    "F U R U' R' F'",
+    "F R U R' U' F'",
+    "R' U' R' F R F' U R",
+    "R' U' R' F R F' R' F R F' U R",
+    "F R U R' U' R U R' U' F'",
+    "r U' r2 U r2 U r2 U' r",
+    "r' U r2 U' r2 U' r2 U r'",
+    "F U R U' R' U R U' R' F'",
+    "R U R' U R U' B U' B' R'",
+    "r' U' R U' R' U R U' R' U2 r",
+    "r U R' U R U' R' U R U2 r'",
+    "R U2 R2 U' R U' R' U2 F R F'",
+    "r' U' r U' R' U R U' R' U R r' U r",
+    "R U R' U' M' U R U' r'",
+];
+
+/// The PLL algorithms, by name.
+#[rustfmt::skip]
+pub(crate) const PLL: [(&str, &str); 21] = [
+    ("Aa", "x R' U R' D2 R U' R' D2 R2 x'"),
+    ("Ab", "x R2 D2 R U R' D2 R U' R x'"),
+    ("E", "x' R U' R' D R U R' D' R U R' D R U' R' D' x"),
+    ("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    ("Ga", "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+    ("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    ("Gc", "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+    ("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    ("H", "M2 U M2 U2 M2 U M2"),
+    ("Ja", "R' U L' U2 R U' R' U2 R L"),
+    ("Jb", "R U R' F' R U R' U' R' F R2 U' R'"),
+    ("Na", "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'"),
+    ("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    ("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    ("Rb", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    ("T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    ("Ua", "M2 U M U2 M' U M2"),
+    ("Ub", "M2 U' M U2 M' U' M2"),
+    ("V", "R' U R' U' B' R' B2 U' B' U B' R B R"),
+    ("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    ("Z", "M' U M2 U M2 U M' U2 M2"),
+];
+
+/// The turns of the top layer that line a case up with its algorithm.
+const AUF: [&[Move]; 4] = [&[], &[Move::U], &[Move::U2], &[Move::UP]];
+
+const STAGE_NAMES: [&str; 4] = ["Cross", "F2L", "OLL", "PLL"];
+
+struct Tables {
+    oll: Vec<Vec<Move>>,
+    pll: Vec<Vec<Move>>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let parse = |alg| notation::parse(alg).unwrap();
+        Tables {
+            oll: OLL.iter().map(|&alg| parse(alg)).collect(),
+            pll: PLL.iter().map(|&(_, alg)| parse(alg)).collect(),
+        }
+    })
+}
+
+fn make_moves(cube: &mut CubieCube, moves: &[Move]) {
+    for &m in moves {
+        cube.make_move(m);
+    }
+}
+
+/// Tries each algorithm after each turn of the top layer, giving the moves of
+/// the first combination that leaves the cube as `done` wants.
+fn recognise(
+    cube: &CubieCube,
+    algs: &[Vec<Move>],
+    done: impl Fn(&CubieCube) -> bool,
+) -> Option<Vec<Move>> {
+    AUF.iter()
+        .flat_map(|&auf| algs.iter().map(move |alg| [auf, alg].concat()))
+        .find(|moves| {
+            let mut cube = cube.clone();
+            make_moves(&mut cube, moves);
+            done(&cube)
+        })
+}
+
+fn last_layer_oriented(cube: &CubieCube) -> bool {
+    cube.co[..4] == [0; 4] && cube.eo[..4] == [0; 4]
+}
+
+/// Orients the last layer, once the first two layers are solved.
+pub(crate) fn solve_oll(cube: &mut CubieCube) -> Vec<Move> {
+    if last_layer_oriented(cube) {
+        return Vec::new();
+    }
+    let moves = recognise(cube, &tables().oll, last_layer_oriented)
+        .expect("the first two layers are solved");
+    make_moves(cube, &moves);
+    moves
+}
+
+/// Permutes the last layer, once the rest of the cube is solved.
+pub(crate) fn solve_pll(cube: &mut CubieCube) -> Vec<Move> {
+    let solved = |cube: &CubieCube| {
+        AUF.iter().find(|&&auf| {
+            let mut cube = cube.clone();
+            make_moves(&mut cube, auf);
+            cube == CubieCube::SOLVED
+        })
+    };
+    let mut moves = match solved(cube) {
+        Some(_) => Vec::new(),
+        None => recognise(cube, &tables().pll, |cube| solved(cube).is_some())
+            .expect("the last layer is oriented"),
+    };
+    make_moves(cube, &moves);
+    let auf = solved(cube).unwrap();
+    make_moves(cube, auf);
+    moves.extend_from_slice(auf);
+    moves
+}
+
+/// Solves the cube with CFOP, giving the moves of each stage separately.
+///
+/// Panics if the cube can't be solved.
+pub fn solve(cube: &Cube) -> Vec<Stage> {
+    solve_in(cube, Metric::Htm)
+}
+
+/// Solves the cube like [`solve`], with the cross and each pair as short as
+/// they can be in `metric`.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_in(cube: &Cube, metric: Metric) -> Vec<Stage> {
+    let cross = cross::solve_in(cube, metric);
+    let mut cube = CubieCube::from_solvable_cube(cube);
+    make_moves(&mut cube, &cross);
+
+    let mut f2l = Vec::new();
+    let mut solved = Vec::new();
+    let mut remaining = Slot::ALL.to_vec();
+    while !remaining.is_empty() {
+        let (slot, moves) = cross::solve_next_pair(&cube, &solved, &remaining, metric);
+        make_moves(&mut cube, &moves);
+        f2l.extend(moves);
+        remaining.retain(|&s| s != slot);
+        solved.push(slot);
+    }
+
+    let oll = solve_oll(&mut cube);
+    let pll = solve_pll(&mut cube);
+
+    [cross, f2l, oll, pll]
+        .into_iter()
+        .zip(STAGE_NAMES)
+        .map(|(moves, name)| Stage { name, moves })
+        .collect()
+}
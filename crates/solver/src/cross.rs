@@ -0,0 +1,350 @@
+//! Solving the cross, the four bottom layer edges, in as few moves as
+//! possible.
+//!
+//! The number of moves needed to solve the cross from any position of its
+//! edges is looked up in a pattern database of the bottom edges, so a shortest
+//! cross is found by always making a move that brings that number down. No
+//! cross needs more than eight moves.
+//!
+//! [`solve_all`] lists every shortest cross, for picking the one that is
+//! easiest to turn. [`solve_in`] finds a cross that is shortest in another
+//! metric, with a database that counts the moves in that metric.
+//!
+//! Speedcubers who are colour neutral build the cross on whichever colour is
+//! cheapest, so [`solve_colour_neutral`] compares the crosses of several
+//! colours. [`plan_pairs`] goes further, looking for short ways to solve the
+//! cross together with one or more of the first two layer pairs, as practised
+//! during inspection.
+
+use std::sync::OnceLock;
+
+use crate::cubie::{face_turn_move, redundant_pair, CubieCube, N_MOVES};
+use crate::metric::Metric;
+use crate::search::{
+    database, database_in, face_location_move, face_turn_costs, face_turn_metric, PerMetric, State,
+};
+use crate::{Colour, Cube, FaceName, Move, BOTTOM_ROTATIONS};
+
+/// A cross, solved after turning the cube so that its colour is on the bottom.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cross {
+    pub colour: Colour,
+    /// The whole cube rotation that brings the cross colour to the bottom.
+    pub rotation: Vec<Move>,
+    /// The moves that solve the cross, made after the rotation.
+    pub moves: Vec<Move>,
+}
+
+/// The edges of the bottom layer, as indices into the corners followed by the
+/// edges of the pattern databases.
+const BOTTOM_EDGES: [usize; 4] = [12, 13, 14, 15];
+
+/// The cost of the moves needed to solve the cross in `metric`.
+fn distance(state: &State, metric: Metric) -> u8 {
+    static DATABASES: PerMetric<Vec<u8>> = PerMetric::new();
+    let metric = face_turn_metric(metric);
+    let database = DATABASES.get_or_init(metric, || {
+        database_in(
+            face_location_move(),
+            &BOTTOM_EDGES,
+            &face_turn_costs(metric),
+        )
+    });
+    database[state.group_index(&BOTTOM_EDGES)]
+}
+
+/// The face turns that bring the cross closer to solved by what they cost in
+/// `metric`.
+fn next_moves(state: State, metric: Metric) -> impl Iterator<Item = (usize, State)> {
+    let (costs, target) = (face_turn_costs(metric), distance(&state, metric) as usize);
+    (0..N_MOVES)
+        .map(move |m| (m, state.make_face_turn(m)))
+        .filter(move |&(m, next)| distance(&next, metric) as usize + costs[m] == target)
+}
+
+/// Finds a shortest solution to the cross on the bottom face.
+///
+/// Panics if the cube can't be solved.
+pub fn solve(cube: &Cube) -> Vec<Move> {
+    solve_in(cube, Metric::Htm)
+}
+
+/// Finds a shortest solution in `metric` to the cross on the bottom face.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_in(cube: &Cube, metric: Metric) -> Vec<Move> {
+    let mut state = State::from_cubie(&CubieCube::from_solvable_cube(cube));
+    let mut moves = Vec::new();
+    while distance(&state, metric) > 0 {
+        let (m, next) = next_moves(state, metric)
+            .next()
+            .expect("pattern database is exact");
+        moves.push(face_turn_move(m));
+        state = next;
+    }
+    moves
+}
+
+/// Finds every shortest solution to the cross on the bottom face, so that the
+/// easiest to turn can be picked. Sequences that differ only in the order of
+/// turns of opposite faces are all included.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_all(cube: &Cube) -> Vec<Vec<Move>> {
+    fn extend(state: State, path: &mut Vec<Move>, solutions: &mut Vec<Vec<Move>>) {
+        if distance(&state, Metric::Htm) == 0 {
+            solutions.push(path.clone());
+            return;
+        }
+        for (m, next) in next_moves(state, Metric::Htm) {
+            path.push(face_turn_move(m));
+            extend(next, path, solutions);
+            path.pop();
+        }
+    }
+
+    let state = State::from_cubie(&CubieCube::from_solvable_cube(cube));
+    let mut solutions = Vec::new();
+    extend(state, &mut Vec::new(), &mut solutions);
+    solutions
+}
+
+/// Rotates the cube so that `colour` is on the bottom, giving the rotated cube
+/// and the rotation.
+fn rotate_to_bottom(cube: &Cube, colour: Colour) -> (Cube, Vec<Move>) {
+    BOTTOM_ROTATIONS
+        .iter()
+        .map(|&rotation| {
+            let mut rotated = cube.clone();
+            for &m in rotation {
+                rotated.make_move(m);
+            }
+            (rotated, rotation.to_vec())
+        })
+        .find(|(rotated, _)| rotated.face_colour(FaceName::Bottom) == colour)
+        .unwrap_or_else(|| panic!("no face of the cube is {:?}", colour))
+}
+
+/// Finds a shortest cross for each of `colours`, in the same order.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_each_colour(cube: &Cube, colours: &[Colour]) -> Vec<Cross> {
+    colours
+        .iter()
+        .map(|&colour| {
+            let (rotated, rotation) = rotate_to_bottom(cube, colour);
+            Cross {
+                colour,
+                moves: solve(&rotated),
+                rotation,
+            }
+        })
+        .collect()
+}
+
+/// Finds the shortest cross among `colours`, e.g. [`Colour::ALL`] to try every
+/// colour or `&[Colour::White, Colour::Yellow]` for just those two. Ties go to
+/// the colour listed first.
+///
+/// Panics if `colours` is empty or the cube can't be solved.
+pub fn solve_colour_neutral(cube: &Cube, colours: &[Colour]) -> Cross {
+    solve_each_colour(cube, colours)
+        .into_iter()
+        .min_by_key(|cross| cross.moves.len())
+        .expect("no colours to choose from")
+}
+
+/// A slot of the first two layers, named after the faces it is between, which
+/// holds a bottom corner and the middle layer edge above it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Slot {
+    FrontRight,
+    FrontLeft,
+    BackLeft,
+    BackRight,
+}
+
+impl Slot {
+    pub const ALL: [Slot; 4] = [
+        Slot::FrontRight,
+        Slot::FrontLeft,
+        Slot::BackLeft,
+        Slot::BackRight,
+    ];
+
+    /// The corner and edge of the slot and the two cross edges beside it, as
+    /// indices into the corners followed by the edges of the pattern
+    /// databases. Including the cross edges makes for a much better estimate
+    /// of the moves needed than the pair alone.
+    fn pieces(self) -> [usize; 4] {
+        match self {
+            Slot::FrontRight => [4, 16, 12, 13],
+            Slot::FrontLeft => [5, 17, 13, 14],
+            Slot::BackLeft => [6, 18, 14, 15],
+            Slot::BackRight => [7, 19, 15, 12],
+        }
+    }
+}
+
+/// The number of moves needed to solve each slot's pair along with the cross
+/// edges beside it.
+fn slot_databases() -> &'static [Vec<u8>; 4] {
+    static DATABASES: OnceLock<[Vec<u8>; 4]> = OnceLock::new();
+    DATABASES.get_or_init(|| Slot::ALL.map(|slot| database(face_location_move(), &slot.pieces())))
+}
+
+/// A way to solve the cross together with the pairs of some slots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub colour: Colour,
+    /// The slots solved along with the cross, as seen after the rotation.
+    pub slots: Vec<Slot>,
+    /// The whole cube rotation that brings the cross colour to the bottom.
+    pub rotation: Vec<Move>,
+    pub moves: Vec<Move>,
+}
+
+struct Search<'a> {
+    /// The slots that must all be solved.
+    slots: &'a [Slot],
+    /// The slots of which at least one must be solved, if any are given.
+    any_of: &'a [Slot],
+    metric: Metric,
+    /// What each face turn counts as in the metric.
+    costs: [usize; N_MOVES],
+    path: Vec<usize>,
+}
+
+fn slot_distance(state: &State, slot: Slot) -> u8 {
+    slot_databases()[slot as usize][state.group_index(&slot.pieces())]
+}
+
+impl Search<'_> {
+    /// The most moves needed by the cross or any of the pairs on their own.
+    /// The pairs' databases count every move as one, which is never more than
+    /// a move costs.
+    fn bound(&self, state: &State) -> usize {
+        let any_of = self
+            .any_of
+            .iter()
+            .map(|&slot| slot_distance(state, slot))
+            .min()
+            .unwrap_or(0);
+        self.slots
+            .iter()
+            .map(|&slot| slot_distance(state, slot))
+            .fold(distance(state, self.metric).max(any_of), u8::max) as usize
+    }
+
+    /// Searches for solutions costing exactly `depth` more.
+    fn search(&mut self, state: State, depth: usize) -> bool {
+        let bound = self.bound(&state);
+        if depth == 0 {
+            return bound == 0;
+        }
+        if bound > depth {
+            return false;
+        }
+
+        for m in 0..N_MOVES {
+            if let Some(&prev) = self.path.last() {
+                if redundant_pair(prev, m) {
+                    continue;
+                }
+            }
+            let Some(depth) = depth.checked_sub(self.costs[m]) else {
+                continue;
+            };
+            self.path.push(m);
+            if self.search(state.make_face_turn(m), depth) {
+                return true;
+            }
+            self.path.pop();
+        }
+        false
+    }
+}
+
+/// Finds a shortest solution to the cross on the bottom face together with
+/// the pairs of `slots`, if there is one of at most `max_length` moves.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_with_pairs(cube: &Cube, slots: &[Slot], max_length: usize) -> Option<Vec<Move>> {
+    let state = State::from_cubie(&CubieCube::from_solvable_cube(cube));
+    let mut search = Search {
+        slots,
+        any_of: &[],
+        metric: Metric::Htm,
+        costs: face_turn_costs(Metric::Htm),
+        path: Vec::new(),
+    };
+    let found = (search.bound(&state)..=max_length).any(|depth| search.search(state, depth));
+    found.then(|| search.path.iter().map(|&m| face_turn_move(m)).collect())
+}
+
+/// Finds a cheapest way in `metric` to solve the pair of any one of `next`,
+/// keeping the cross and the pairs of `solved`, giving the slot it goes in and
+/// the moves.
+pub(crate) fn solve_next_pair(
+    cube: &CubieCube,
+    solved: &[Slot],
+    next: &[Slot],
+    metric: Metric,
+) -> (Slot, Vec<Move>) {
+    let mut state = State::from_cubie(cube);
+    let mut search = Search {
+        slots: solved,
+        any_of: next,
+        metric,
+        costs: face_turn_costs(metric),
+        path: Vec::new(),
+    };
+    let mut depth = search.bound(&state);
+    while !search.search(state, depth) {
+        depth += 1;
+    }
+    for &m in &search.path {
+        state = state.make_face_turn(m);
+    }
+    let slot = *next
+        .iter()
+        .find(|&&slot| slot_distance(&state, slot) == 0)
+        .unwrap();
+    (
+        slot,
+        search.path.iter().map(|&m| face_turn_move(m)).collect(),
+    )
+}
+
+/// Plans the cross with `pairs` of the first two layer pairs, e.g. 1 for an
+/// X-cross or 2 for an XX-cross. Gives the shortest solution of at most
+/// `max_length` moves for each of `colours` and each choice of slots, leaving
+/// out those that take longer.
+///
+/// Panics if the cube can't be solved.
+pub fn plan_pairs(cube: &Cube, colours: &[Colour], pairs: usize, max_length: usize) -> Vec<Plan> {
+    let mut plans = Vec::new();
+    for &colour in colours {
+        let (rotated, rotation) = rotate_to_bottom(cube, colour);
+        for mask in 0..1 << Slot::ALL.len() {
+            if u32::count_ones(mask) as usize != pairs {
+                continue;
+            }
+            let slots: Vec<Slot> = Slot::ALL
+                .into_iter()
+                .enumerate()
+                .filter(|&(i, _)| mask & 1 << i != 0)
+                .map(|(_, slot)| slot)
+                .collect();
+            if let Some(moves) = solve_with_pairs(&rotated, &slots, max_length) {
+                plans.push(Plan {
+                    colour,
+                    slots,
+                    rotation: rotation.clone(),
+                    moves,
+                });
+            }
+        }
+    }
+    plans
+}
@@ -0,0 +1,537 @@
+//! The cube as a set of pieces ("cubies"), rather than stickers.
+//!
+//! Pieces and their positions are named after the faces they touch, e.g. the
+//! corner `Urf` sits between the top (up), right and front faces. A cube is
+//! described by which piece occupies each position and how it is twisted or
+//! flipped there, relative to the centres.
+//!
+//! [`CubieCube::from_cube`] and [`CubieCube::to_cube`] convert to and from the
+//! stickers of a [`Cube`].
+
+use crate::{Cube, CubeError, FaceName, Move};
+use FaceName::*;
+
+/// A corner piece, or the position it belongs in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Corner {
+    Urf,
+    Ufl,
+    Ulb,
+    Ubr,
+    Dfr,
+    Dlf,
+    Dbl,
+    Drb,
+}
+
+use Corner::*;
+
+pub const CORNERS: [Corner; 8] = [Urf, Ufl, Ulb, Ubr, Dfr, Dlf, Dbl, Drb];
+
+/// An edge piece, or the position it belongs in.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Edge {
+    Ur,
+    Uf,
+    Ul,
+    Ub,
+    Dr,
+    Df,
+    Dl,
+    Db,
+    Fr,
+    Fl,
+    Bl,
+    Br,
+}
+
+use Edge::*;
+
+pub const EDGES: [Edge; 12] = [Ur, Uf, Ul, Ub, Dr, Df, Dl, Db, Fr, Fl, Bl, Br];
+
+/// The stickers of each corner position, starting with the one on the top or
+/// bottom face and going clockwise around the corner.
+#[rustfmt::skip]
+pub(crate) const CORNER_FACELETS: [[(FaceName, usize); 3]; 8] = [
+    [(Top, 8), (Right, 0), (Front, 2)],
+    [(Top, 6), (Front, 0), (Left, 2)],
+    [(Top, 0), (Left, 0), (Back, 2)],
+    [(Top, 2), (Back, 0), (Right, 2)],
+    [(Bottom, 2), (Front, 8), (Right, 6)],
+    [(Bottom, 0), (Left, 8), (Front, 6)],
+    [(Bottom, 6), (Back, 8), (Left, 6)],
+    [(Bottom, 8), (Right, 8), (Back, 6)],
+];
+
+/// The stickers of each edge position, starting with the one on the top or
+/// bottom face, or for the middle layer, the front or back face.
+#[rustfmt::skip]
+pub(crate) const EDGE_FACELETS: [[(FaceName, usize); 2]; 12] = [
+    [(Top, 5), (Right, 1)],
+    [(Top, 7), (Front, 1)],
+    [(Top, 3), (Left, 1)],
+    [(Top, 1), (Back, 1)],
+    [(Bottom, 5), (Right, 7)],
+    [(Bottom, 1), (Front, 7)],
+    [(Bottom, 3), (Left, 7)],
+    [(Bottom, 7), (Back, 7)],
+    [(Front, 5), (Right, 3)],
+    [(Front, 3), (Left, 5)],
+    [(Back, 5), (Left, 3)],
+    [(Back, 3), (Right, 5)],
+];
+
+/// A cube, given as the piece at each position and its orientation there.
+///
+/// Corner orientations count clockwise twists (0-2) of the corner's top/bottom
+/// sticker away from the top/bottom face. Edge orientations are 1 when the edge
+/// is flipped, which only quarter turns of the front and back faces can do.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CubieCube {
+    pub cp: [Corner; 8],
+    pub co: [u8; 8],
+    pub ep: [Edge; 12],
+    pub eo: [u8; 12],
+}
+
+/// The six face turns, in the order they are indexed by the solvers: the
+/// clockwise quarter turn of face `f` has index `3 * f`, the half turn `3 * f + 1`
+/// and the anti-clockwise quarter turn `3 * f + 2`.
+#[rustfmt::skip]
+const FACE_TURNS: [CubieCube; 6] = [
+    // U
+    CubieCube {
+        cp: [Ubr, Urf, Ufl, Ulb, Dfr, Dlf, Dbl, Drb],
+        co: [0, 0, 0, 0, 0, 0, 0, 0],
+        ep: [Ub, Ur, Uf, Ul, Dr, Df, Dl, Db, Fr, Fl, Bl, Br],
+        eo: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    },
+    // R
+    CubieCube {
+        cp: [Dfr, Ufl, Ulb, Urf, Drb, Dlf, Dbl, Ubr],
+        co: [2, 0, 0, 1, 1, 0, 0, 2],
+        ep: [Fr, Uf, Ul, Ub, Br, Df, Dl, Db, Dr, Fl, Bl, Ur],
+        eo: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    },
+    // F
+    CubieCube {
+        cp: [Ufl, Dlf, Ulb, Ubr, Urf, Dfr, Dbl, Drb],
+        co: [1, 2, 0, 0, 2, 1, 0, 0],
+        ep: [Ur, Fl, Ul, Ub, Dr, Fr, Dl, Db, Uf, Df, Bl, Br],
+        eo: [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0],
+    },
+    // D
+    CubieCube {
+        cp: [Urf, Ufl, Ulb, Ubr, Dlf, Dbl, Drb, Dfr],
+        co: [0, 0, 0, 0, 0, 0, 0, 0],
+        ep: [Ur, Uf, Ul, Ub, Df, Dl, Db, Dr, Fr, Fl, Bl, Br],
+        eo: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    },
+    // L
+    CubieCube {
+        cp: [Urf, Ulb, Dbl, Ubr, Dfr, Ufl, Dlf, Drb],
+        co: [0, 1, 2, 0, 0, 2, 1, 0],
+        ep: [Ur, Uf, Bl, Ub, Dr, Df, Fl, Db, Fr, Ul, Dl, Br],
+        eo: [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    },
+    // B
+    CubieCube {
+        cp: [Urf, Ufl, Ubr, Drb, Dfr, Dlf, Ulb, Dbl],
+        co: [0, 0, 1, 2, 0, 0, 2, 1],
+        ep: [Ur, Uf, Ul, Br, Dr, Df, Dl, Bl, Fr, Fl, Ub, Db],
+        eo: [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+    },
+];
+
+/// The number of face turns, counting each face's quarter, half and
+/// anti-clockwise quarter turn separately.
+pub(crate) const N_MOVES: usize = 18;
+
+/// The number of values of each coordinate.
+pub(crate) const N_TWIST: usize = 2187;
+pub(crate) const N_FLIP: usize = 2048;
+pub(crate) const N_SLICE: usize = 495;
+pub(crate) const N_CORNER_PERM: usize = 40320;
+pub(crate) const N_UD_EDGE_PERM: usize = 40320;
+pub(crate) const N_SLICE_PERM: usize = 24;
+
+/// The move for the face turn with index `m`.
+pub(crate) fn face_turn_move(m: usize) -> Move {
+    const MOVES: [Move; N_MOVES] = [
+        Move::U,
+        Move::U2,
+        Move::UP,
+        Move::R,
+        Move::R2,
+        Move::RP,
+        Move::F,
+        Move::F2,
+        Move::FP,
+        Move::D,
+        Move::D2,
+        Move::DP,
+        Move::L,
+        Move::L2,
+        Move::LP,
+        Move::B,
+        Move::B2,
+        Move::BP,
+    ];
+    MOVES[m]
+}
+
+/// The face turn index of a move, if it is a face turn.
+pub(crate) fn face_turn_index(action: Move) -> Option<usize> {
+    Some(match action {
+        Move::U => 0,
+        Move::R => 3,
+        Move::F => 6,
+        Move::D => 9,
+        Move::L => 12,
+        Move::B => 15,
+        Move::UP => 2,
+        Move::RP => 5,
+        Move::FP => 8,
+        Move::DP => 11,
+        Move::LP => 14,
+        Move::BP => 17,
+        Move::U2 => 1,
+        Move::R2 => 4,
+        Move::F2 => 7,
+        Move::D2 => 10,
+        Move::L2 => 13,
+        Move::B2 => 16,
+        _ => return None,
+    })
+}
+
+/// Whether two consecutive face turns should never both be searched: turning
+/// the same face twice, or opposite faces in the wrong order.
+pub(crate) fn redundant_pair(prev: usize, m: usize) -> bool {
+    let (prev_face, face) = (prev / 3, m / 3);
+    face == prev_face || face + 3 == prev_face
+}
+
+fn binomial(n: usize, k: usize) -> usize {
+    if n < k {
+        return 0;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+/// Whether the permutation is odd.
+pub(crate) fn parity(perm: &[u8]) -> bool {
+    let mut odd = false;
+    for i in 0..perm.len() {
+        for j in i + 1..perm.len() {
+            if perm[j] < perm[i] {
+                odd = !odd;
+            }
+        }
+    }
+    odd
+}
+
+/// The rank of a permutation of `0..perm.len()`, in lexicographic order.
+fn perm_index(perm: &[u8]) -> usize {
+    let n = perm.len();
+    let mut index = 0;
+    for i in 0..n {
+        let smaller = perm[i + 1..].iter().filter(|&&p| p < perm[i]).count();
+        index = index * (n - i) + smaller;
+    }
+    index
+}
+
+/// The inverse of [`perm_index`].
+fn perm_from_index(mut index: usize, perm: &mut [u8]) {
+    let n = perm.len();
+    let mut digits = [0; 12];
+    for i in (0..n).rev() {
+        digits[i] = index % (n - i);
+        index /= n - i;
+    }
+    let mut available: Vec<u8> = (0..n as u8).collect();
+    for i in 0..n {
+        perm[i] = available.remove(digits[i]);
+    }
+}
+
+impl CubieCube {
+    pub const SOLVED: CubieCube = CubieCube {
+        cp: CORNERS,
+        co: [0; 8],
+        ep: EDGES,
+        eo: [0; 12],
+    };
+
+    /// Reads the pieces off a cube's stickers, relative to its centres.
+    ///
+    /// Fails if any position holds a piece that doesn't exist. The pieces
+    /// aren't checked any further; see [`CubieCube::validate`].
+    pub fn from_cube(cube: &Cube) -> Result<CubieCube, CubeError> {
+        let mut result = CubieCube::SOLVED;
+        let colour = |(face, idx): (FaceName, usize)| cube[face].0[idx];
+        let top = cube.face_colour(Top);
+        let bottom = cube.face_colour(Bottom);
+
+        for (pos, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let colours = facelets.map(colour);
+            let impossible = CubeError::ImpossibleCorner {
+                position: CORNERS[pos],
+                colours,
+            };
+            let ori = (0..3)
+                .find(|&i| colours[i] == top || colours[i] == bottom)
+                .ok_or(impossible)?;
+            let c1 = colours[(ori + 1) % 3];
+            let c2 = colours[(ori + 2) % 3];
+            let piece = (0..8)
+                .find(|&piece| {
+                    colours[ori] == cube.face_colour(CORNER_FACELETS[piece][0].0)
+                        && c1 == cube.face_colour(CORNER_FACELETS[piece][1].0)
+                        && c2 == cube.face_colour(CORNER_FACELETS[piece][2].0)
+                })
+                .ok_or(impossible)?;
+            result.cp[pos] = CORNERS[piece];
+            result.co[pos] = ori as u8;
+        }
+
+        for (pos, facelets) in EDGE_FACELETS.iter().enumerate() {
+            let colours = facelets.map(colour);
+            let (piece, ori) = (0..12)
+                .flat_map(|piece| [(piece, 0), (piece, 1)])
+                .find(|&(piece, ori)| {
+                    let home = EDGE_FACELETS[piece];
+                    let (h0, h1) = (
+                        cube.face_colour(home[ori].0),
+                        cube.face_colour(home[1 - ori].0),
+                    );
+                    colours == [h0, h1]
+                })
+                .ok_or(CubeError::ImpossibleEdge {
+                    position: EDGES[pos],
+                    colours,
+                })?;
+            result.ep[pos] = EDGES[piece];
+            result.eo[pos] = ori as u8;
+        }
+
+        Ok(result)
+    }
+
+    /// Reads the pieces off a cube's stickers, like [`CubieCube::from_cube`].
+    ///
+    /// Panics with the reason if the cube can't be solved.
+    pub(crate) fn from_solvable_cube(cube: &Cube) -> CubieCube {
+        if let Err(err) = cube.validate() {
+            panic!("cube can't be solved: {}", err);
+        }
+        CubieCube::from_cube(cube).unwrap()
+    }
+
+    /// Checks that the cube can be solved: every piece appears exactly once,
+    /// the corner twists and edge flips cancel out, and the corner and edge
+    /// permutations have the same parity.
+    pub fn validate(&self) -> Result<(), CubeError> {
+        for (pos, &corner) in self.cp.iter().enumerate() {
+            if let Some(other) = self.cp[pos + 1..].iter().position(|&c| c == corner) {
+                return Err(CubeError::DuplicateCorner {
+                    corner,
+                    positions: [CORNERS[pos], CORNERS[pos + 1 + other]],
+                });
+            }
+        }
+        for (pos, &edge) in self.ep.iter().enumerate() {
+            if let Some(other) = self.ep[pos + 1..].iter().position(|&e| e == edge) {
+                return Err(CubeError::DuplicateEdge {
+                    edge,
+                    positions: [EDGES[pos], EDGES[pos + 1 + other]],
+                });
+            }
+        }
+
+        let twist = self.co.iter().sum::<u8>() % 3;
+        if twist != 0 {
+            return Err(CubeError::CornerTwist { twist });
+        }
+        if self.eo.iter().sum::<u8>() % 2 != 0 {
+            return Err(CubeError::EdgeFlip);
+        }
+        if parity(&self.cp.map(|c| c as u8)) != parity(&self.ep.map(|e| e as u8)) {
+            return Err(CubeError::PermutationParity);
+        }
+        Ok(())
+    }
+
+    /// Applies `other` to this cube, as if it were a sequence of moves.
+    pub fn multiply(&mut self, other: &CubieCube) {
+        let mut result = CubieCube::SOLVED;
+        for i in 0..8 {
+            let from = other.cp[i] as usize;
+            result.cp[i] = self.cp[from];
+            result.co[i] = (self.co[from] + other.co[i]) % 3;
+        }
+        for i in 0..12 {
+            let from = other.ep[i] as usize;
+            result.ep[i] = self.ep[from];
+            result.eo[i] = (self.eo[from] + other.eo[i]) % 2;
+        }
+        *self = result;
+    }
+
+    /// Paints the pieces onto the stickers of a cube with the colours of
+    /// [`Cube::SOLVED`].
+    pub fn to_cube(&self) -> Cube {
+        let mut cube = Cube::SOLVED;
+        for (pos, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let home = CORNER_FACELETS[self.cp[pos] as usize];
+            let ori = self.co[pos] as usize;
+            for (i, &(face, _)) in home.iter().enumerate() {
+                let (to_face, to_idx) = facelets[(i + ori) % 3];
+                cube[to_face].0[to_idx] = Cube::SOLVED.face_colour(face);
+            }
+        }
+        for (pos, facelets) in EDGE_FACELETS.iter().enumerate() {
+            let home = EDGE_FACELETS[self.ep[pos] as usize];
+            let ori = self.eo[pos] as usize;
+            for (i, &(face, _)) in home.iter().enumerate() {
+                let (to_face, to_idx) = facelets[(i + ori) % 2];
+                cube[to_face].0[to_idx] = Cube::SOLVED.face_colour(face);
+            }
+        }
+        cube
+    }
+
+    /// Makes a move. Slice and wide turns move the centres, so the pieces are
+    /// read back relative to where the centres end up, as
+    /// [`CubieCube::from_cube`] would after making the move on the stickers.
+    pub fn make_move(&mut self, action: Move) {
+        match face_turn_index(action) {
+            Some(m) => self.make_face_turn(m),
+            None => {
+                let mut cube = self.to_cube();
+                cube.make_move(action);
+                *self = CubieCube::from_cube(&cube).unwrap();
+            }
+        }
+    }
+
+    /// Makes the face turn with index `m`.
+    pub(crate) fn make_face_turn(&mut self, m: usize) {
+        for _ in 0..m % 3 + 1 {
+            self.multiply(&FACE_TURNS[m / 3]);
+        }
+    }
+
+    /// The orientation of the corners, 0..2187.
+    pub fn twist(&self) -> usize {
+        self.co[..7].iter().fold(0, |acc, &o| acc * 3 + o as usize)
+    }
+
+    pub fn set_twist(&mut self, mut twist: usize) {
+        let mut total = 0;
+        for i in (0..7).rev() {
+            self.co[i] = (twist % 3) as u8;
+            total += self.co[i];
+            twist /= 3;
+        }
+        self.co[7] = (3 - total % 3) % 3;
+    }
+
+    /// The orientation of the edges, 0..2048.
+    pub fn flip(&self) -> usize {
+        self.eo[..11].iter().fold(0, |acc, &o| acc * 2 + o as usize)
+    }
+
+    pub fn set_flip(&mut self, mut flip: usize) {
+        let mut total = 0;
+        for i in (0..11).rev() {
+            self.eo[i] = (flip % 2) as u8;
+            total += self.eo[i];
+            flip /= 2;
+        }
+        self.eo[11] = total % 2;
+    }
+
+    /// Which positions the four middle layer edges occupy, ignoring their
+    /// order, 0..495. 0 when they are all in the middle layer.
+    pub fn slice(&self) -> usize {
+        let mut result = 0;
+        let mut seen = 0;
+        for j in (0..12).rev() {
+            if self.ep[j] as usize >= Fr as usize {
+                result += binomial(11 - j, seen + 1);
+                seen += 1;
+            }
+        }
+        result
+    }
+
+    pub fn set_slice(&mut self, mut slice: usize) {
+        const SLICE_EDGES: [Edge; 4] = [Fr, Fl, Bl, Br];
+        const OTHER_EDGES: [Edge; 8] = [Ur, Uf, Ul, Ub, Dr, Df, Dl, Db];
+        let mut is_slice = [false; 12];
+        let mut remaining = 4;
+        for (j, is_slice) in is_slice.iter_mut().enumerate() {
+            if remaining > 0 && slice >= binomial(11 - j, remaining) {
+                slice -= binomial(11 - j, remaining);
+                *is_slice = true;
+                remaining -= 1;
+            }
+        }
+        let (mut slice_edges, mut other_edges) = (SLICE_EDGES.iter(), OTHER_EDGES.iter());
+        for (j, &is_slice) in is_slice.iter().enumerate() {
+            self.ep[j] = if is_slice {
+                *slice_edges.next().unwrap()
+            } else {
+                *other_edges.next().unwrap()
+            };
+        }
+    }
+
+    /// The permutation of the corners, 0..40320.
+    pub fn corner_perm(&self) -> usize {
+        perm_index(&self.cp.map(|c| c as u8))
+    }
+
+    pub fn set_corner_perm(&mut self, index: usize) {
+        let mut perm = [0; 8];
+        perm_from_index(index, &mut perm);
+        self.cp = perm.map(|p| CORNERS[p as usize]);
+    }
+
+    /// The permutation of the top and bottom layer edges, 0..40320. Only
+    /// meaningful when those edges are all in the top and bottom layers.
+    pub fn ud_edge_perm(&self) -> usize {
+        perm_index(&self.ep[..8].iter().map(|&e| e as u8).collect::<Vec<_>>())
+    }
+
+    pub fn set_ud_edge_perm(&mut self, index: usize) {
+        let mut perm = [0; 8];
+        perm_from_index(index, &mut perm);
+        for (i, p) in perm.into_iter().enumerate() {
+            self.ep[i] = EDGES[p as usize];
+        }
+    }
+
+    /// The permutation of the middle layer edges, 0..24. Only meaningful when
+    /// those edges are all in the middle layer.
+    pub fn slice_perm(&self) -> usize {
+        perm_index(
+            &self.ep[8..]
+                .iter()
+                .map(|&e| e as u8 - 8)
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    pub fn set_slice_perm(&mut self, index: usize) {
+        let mut perm = [0; 4];
+        perm_from_index(index, &mut perm);
+        for (i, p) in perm.into_iter().enumerate() {
+            self.ep[8 + i] = EDGES[8 + p as usize];
+        }
+    }
+}
@@ -0,0 +1,308 @@
+//! Kociemba's two-phase algorithm.
+//!
+//! Phase one brings the cube into the subgroup <U, D, R2, L2, F2, B2>, where
+//! every corner and edge is oriented and the middle layer edges are in the
+//! middle layer. Phase two solves the cube using only those moves. Each phase
+//! searches over small coordinates of the cube, using precomputed move tables
+//! to update them and pruning tables for lower bounds on the remaining moves.
+//!
+//! The pruning tables hold the cost of the cheapest moves in the metric being
+//! searched, so the search looks for short solutions in any metric. In the
+//! slice turn and execution turn metrics, where a slice turn counts as one
+//! move, it makes slice turns as well as face turns, and ends with whatever
+//! whole cube rotations bring the cube back to how it was held.
+
+use std::sync::OnceLock;
+
+use crate::cubie::{
+    CubieCube, N_CORNER_PERM, N_FLIP, N_MOVES, N_SLICE, N_SLICE_PERM, N_TWIST, N_UD_EDGE_PERM,
+};
+use crate::metric::Metric;
+use crate::search::{move_coord, move_table, prune_table, PerMetric, Turns, ALL_MOVES};
+use crate::{Cube, Move};
+
+/// The face turns allowed in phase two: all turns of U and D, and half turns
+/// of the other faces.
+const PHASE2_MOVES: [usize; 10] = [0, 1, 2, 4, 7, 9, 10, 11, 13, 16];
+
+/// The longest solution the default search will settle for.
+const DEFAULT_MAX_LENGTH: usize = 21;
+
+/// The longest solution the default search will settle for, in quarter turns.
+const DEFAULT_MAX_QUARTER_TURNS: usize = 28;
+
+struct Tables {
+    twist_move: Vec<[u16; N_MOVES]>,
+    flip_move: Vec<[u16; N_MOVES]>,
+    slice_move: Vec<[u16; N_MOVES]>,
+    corner_perm_move: Vec<[u16; N_MOVES]>,
+    ud_edge_perm_move: Vec<[u16; N_MOVES]>,
+    slice_perm_move: Vec<[u16; N_MOVES]>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| Tables {
+        twist_move: move_table(N_TWIST, &ALL_MOVES, CubieCube::set_twist, CubieCube::twist),
+        flip_move: move_table(N_FLIP, &ALL_MOVES, CubieCube::set_flip, CubieCube::flip),
+        slice_move: move_table(N_SLICE, &ALL_MOVES, CubieCube::set_slice, CubieCube::slice),
+        corner_perm_move: move_table(
+            N_CORNER_PERM,
+            &PHASE2_MOVES,
+            CubieCube::set_corner_perm,
+            CubieCube::corner_perm,
+        ),
+        ud_edge_perm_move: move_table(
+            N_UD_EDGE_PERM,
+            &PHASE2_MOVES,
+            CubieCube::set_ud_edge_perm,
+            CubieCube::ud_edge_perm,
+        ),
+        slice_perm_move: move_table(
+            N_SLICE_PERM,
+            &PHASE2_MOVES,
+            CubieCube::set_slice_perm,
+            CubieCube::slice_perm,
+        ),
+    })
+}
+
+/// The moves searched in a metric and the pruning tables that count them.
+struct Pruning {
+    turns: Turns,
+    /// The moves allowed in phase two, those that only make face turns in
+    /// [`PHASE2_MOVES`].
+    phase2_moves: Vec<usize>,
+    slice_twist: Vec<u8>,
+    slice_flip: Vec<u8>,
+    corner_slice: Vec<u8>,
+    edge_slice: Vec<u8>,
+}
+
+fn pruning(metric: Metric) -> &'static Pruning {
+    static PRUNING: PerMetric<Pruning> = PerMetric::new();
+    PRUNING.get_or_init(metric, || {
+        let t = tables();
+        let turns = Turns::new(metric);
+        let phase1_moves: Vec<usize> = (0..turns.len()).collect();
+        let phase2_moves: Vec<usize> = (0..turns.len())
+            .filter(|&m| turns.face_turns(m).iter().all(|m| PHASE2_MOVES.contains(m)))
+            .collect();
+        Pruning {
+            slice_twist: prune_table(&t.slice_move, &t.twist_move, &turns, &phase1_moves),
+            slice_flip: prune_table(&t.slice_move, &t.flip_move, &turns, &phase1_moves),
+            corner_slice: prune_table(
+                &t.corner_perm_move,
+                &t.slice_perm_move,
+                &turns,
+                &phase2_moves,
+            ),
+            edge_slice: prune_table(
+                &t.ud_edge_perm_move,
+                &t.slice_perm_move,
+                &turns,
+                &phase2_moves,
+            ),
+            turns,
+            phase2_moves,
+        }
+    })
+}
+
+struct Search<'a> {
+    tables: &'a Tables,
+    pruning: &'a Pruning,
+    cube: CubieCube,
+    /// The longest solution still worth finding, in the metric being
+    /// searched. Shrinks as solutions are found.
+    max_length: usize,
+    phase1: Vec<usize>,
+    phase2: Vec<usize>,
+    best: Option<Vec<usize>>,
+}
+
+impl Search<'_> {
+    fn cost(&self, moves: &[usize]) -> usize {
+        moves.iter().map(|&m| self.pruning.turns.cost(m)).sum()
+    }
+
+    fn phase1_bound(&self, twist: usize, flip: usize, slice: usize) -> u8 {
+        let p = self.pruning;
+        p.slice_twist[slice * N_TWIST + twist].max(p.slice_flip[slice * N_FLIP + flip])
+    }
+
+    fn phase2_bound(&self, corners: usize, edges: usize, slice: usize) -> u8 {
+        let p = self.pruning;
+        p.corner_slice[corners * N_SLICE_PERM + slice]
+            .max(p.edge_slice[edges * N_SLICE_PERM + slice])
+    }
+
+    /// Searches for phase one solutions of exactly `depth` more moves, trying
+    /// phase two from each one.
+    fn phase1(&mut self, twist: usize, flip: usize, slice: usize, depth: usize) {
+        if depth == 0 {
+            if twist != 0 || flip != 0 || slice != 0 {
+                return;
+            }
+            // A phase one solution ending in a phase two move would have been
+            // found as a shorter phase one solution already.
+            if let Some(last) = self.phase1.last() {
+                if self.pruning.phase2_moves.contains(last) {
+                    return;
+                }
+            }
+            self.start_phase2();
+            return;
+        }
+        if self.phase1_bound(twist, flip, slice) as usize > depth
+            || self.cost(&self.phase1) + depth > self.max_length
+        {
+            return;
+        }
+
+        let (t, turns) = (self.tables, &self.pruning.turns);
+        for m in 0..turns.len() {
+            if let Some(&prev) = self.phase1.last() {
+                if Turns::redundant(prev, m) {
+                    continue;
+                }
+            }
+            let cost = turns.cost(m);
+            if cost > depth {
+                continue;
+            }
+            let face_turns = turns.face_turns(m);
+            let twist = move_coord(&t.twist_move, twist, face_turns);
+            let flip = move_coord(&t.flip_move, flip, face_turns);
+            let slice = move_coord(&t.slice_move, slice, face_turns);
+            self.phase1.push(m);
+            self.phase1(twist, flip, slice, depth - cost);
+            self.phase1.pop();
+        }
+    }
+
+    /// Finishes the current phase one solution with the shortest phase two
+    /// solution, if that beats the best solution so far.
+    fn start_phase2(&mut self) {
+        // The best solution may have shrunk below this one since its last
+        // move was tried.
+        let Some(max_depth) = self.max_length.checked_sub(self.cost(&self.phase1)) else {
+            return;
+        };
+        let mut cube = self.cube.clone();
+        for &m in &self.phase1 {
+            for &turn in self.pruning.turns.face_turns(m) {
+                cube.make_face_turn(turn);
+            }
+        }
+        let (corners, edges, slice) = (cube.corner_perm(), cube.ud_edge_perm(), cube.slice_perm());
+        let bound = self.phase2_bound(corners, edges, slice) as usize;
+        if (bound..=max_depth).any(|depth| self.phase2(corners, edges, slice, depth)) {
+            let solution: Vec<usize> = self.phase1.iter().chain(&self.phase2).copied().collect();
+            let cost = self.cost(&solution) + self.pruning.turns.restore_cost(&solution);
+            self.max_length = cost.saturating_sub(1);
+            self.best = Some(solution);
+            self.phase2.clear();
+        }
+    }
+
+    /// The cost of the rotations that would end the current solution.
+    fn restore_cost(&self) -> usize {
+        let solution: Vec<usize> = self.phase1.iter().chain(&self.phase2).copied().collect();
+        self.pruning.turns.restore_cost(&solution)
+    }
+
+    fn phase2(&mut self, corners: usize, edges: usize, slice: usize, depth: usize) -> bool {
+        if corners == 0 && edges == 0 && slice == 0 && self.restore_cost() == depth {
+            return true;
+        }
+        if depth == 0 || self.phase2_bound(corners, edges, slice) as usize > depth {
+            return false;
+        }
+
+        let (t, p) = (self.tables, self.pruning);
+        for &m in &p.phase2_moves {
+            if let Some(&prev) = self.phase2.last().or(self.phase1.last()) {
+                if Turns::redundant(prev, m) {
+                    continue;
+                }
+            }
+            let cost = p.turns.cost(m);
+            if cost > depth {
+                continue;
+            }
+            let face_turns = p.turns.face_turns(m);
+            let corners = move_coord(&t.corner_perm_move, corners, face_turns);
+            let edges = move_coord(&t.ud_edge_perm_move, edges, face_turns);
+            let slice = move_coord(&t.slice_perm_move, slice, face_turns);
+            self.phase2.push(m);
+            if self.phase2(corners, edges, slice, depth - cost) {
+                return true;
+            }
+            self.phase2.pop();
+        }
+        false
+    }
+}
+
+/// Solves the cube in at most `max_length` face turns (counting half turns as
+/// one), if possible.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_with_max_length(cube: &Cube, max_length: usize) -> Option<Vec<Move>> {
+    solve_with_max_length_in(cube, max_length, Metric::Htm)
+}
+
+/// Solves the cube in at most `max_length` moves in `metric`, if possible.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_with_max_length_in(
+    cube: &Cube,
+    max_length: usize,
+    metric: Metric,
+) -> Option<Vec<Move>> {
+    let cube = CubieCube::from_solvable_cube(cube);
+    let mut search = Search {
+        tables: tables(),
+        pruning: pruning(metric),
+        cube,
+        max_length,
+        phase1: Vec::new(),
+        phase2: Vec::new(),
+        best: None,
+    };
+    let (twist, flip, slice) = (search.cube.twist(), search.cube.flip(), search.cube.slice());
+    let mut depth = search.phase1_bound(twist, flip, slice) as usize;
+    // Once a solution turns up, the rest of that phase one depth is searched
+    // for shorter ones, which is cheap and catches the cubes that are only a
+    // few moves from solved. Going any deeper rarely pays off.
+    while search.best.is_none() && depth <= search.max_length {
+        search.phase1(twist, flip, slice, depth);
+        depth += 1;
+    }
+
+    let turns = &search.pruning.turns;
+    search.best.map(|best| turns.to_moves(&best))
+}
+
+/// Solves the cube in about 20 face turns (counting half turns as one).
+///
+/// Panics if the cube can't be solved.
+pub fn solve(cube: &Cube) -> Vec<Move> {
+    solve_in(cube, Metric::Htm)
+}
+
+/// Solves the cube in about 20 moves, or about 26 quarter turns, keeping the
+/// solution short in `metric`.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_in(cube: &Cube, metric: Metric) -> Vec<Move> {
+    let start = match metric {
+        Metric::Qtm => DEFAULT_MAX_QUARTER_TURNS,
+        _ => DEFAULT_MAX_LENGTH,
+    };
+    // Searches with ever longer limits until a solution turns up.
+    (start..)
+        .find_map(|max_length| solve_with_max_length_in(cube, max_length, metric))
+        .unwrap()
+}
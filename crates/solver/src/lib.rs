@@ -1,8 +1,23 @@
 use std::fmt::{Display, Write};
 use std::ops::{Index, IndexMut};
 
+pub mod algorithm;
+pub mod blindfold;
+pub mod cfop;
+pub mod cross;
+pub mod cubie;
+pub mod kociemba;
+pub mod metric;
+pub mod notation;
+pub mod optimal;
+pub mod petrus;
+pub mod roux;
+pub mod scramble;
+mod search;
 #[cfg(test)]
 mod tests;
+pub mod thistlethwaite;
+pub mod zz;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
 pub enum Colour {
@@ -14,6 +29,10 @@ pub enum Colour {
     Yellow,
 }
 
+impl Colour {
+    pub const ALL: [Colour; 6] = [White, Red, Blue, Orange, Green, Yellow];
+}
+
 impl Display for Colour {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -29,33 +48,70 @@ impl Display for Colour {
 
 use Colour::*;
 
-use Colour::Blue as B;
-use Colour::Green as G;
-use Colour::Orange as O;
-use Colour::Red as R;
-use Colour::White as W;
-use Colour::Yellow as Y;
-
-// // Old cube
-// #[rustfmt::skip]
-// static EDGES: [[Edge; 4]; 6] = [
-//     [Edge(White, Red), Edge(White, Green), Edge(White, Orange), Edge(White, Yellow)],
-//     [Edge(Red, Blue), Edge(Red, Yellow), Edge(Red, Orange), Edge(Red, White)],
-//     [Edge(Blue, Red), Edge(Blue, Green), Edge(Blue, Orange), Edge(Blue, Yellow)],
-//     [Edge(Orange, White), Edge(Orange, Green), Edge(Orange, Red), Edge(Orange, Blue)],
-//     [Edge(Green, White), Edge(Green, Blue), Edge(Green, Yellow), Edge(Green, Orange)],
-//     [Edge(Yellow, White), Edge(Yellow, Green), Edge(Yellow, Red), Edge(Yellow, Blue)],
-// ];
-// New cube
-#[rustfmt::skip]
-static EDGES: [[Edge; 4]; 6] = [
-    [Edge(White, Red), Edge(White, Blue), Edge(White, Orange), Edge(White, Green)],
-    [Edge(Red, White), Edge(Red, Blue), Edge(Red, Green), Edge(Red, Yellow)],
-    [Edge(Blue, White), Edge(Blue, Red), Edge(Blue, Orange), Edge(Blue, Yellow)],
-    [Edge(Orange, White), Edge(Orange, Blue), Edge(Orange, Green), Edge(Orange, Yellow)],
-    [Edge(Green, White), Edge(Green, Red), Edge(Green, Orange), Edge(Green, Yellow)],
-    [Edge(Yellow, Red), Edge(Yellow, Blue), Edge(Yellow, Orange), Edge(Yellow, Green)],
-];
+/// The colour of each face of a solved cube.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColourScheme {
+    pub top: Colour,
+    pub left: Colour,
+    pub front: Colour,
+    pub right: Colour,
+    pub back: Colour,
+    pub bottom: Colour,
+}
+
+impl ColourScheme {
+    /// White opposite yellow, blue opposite green and red opposite orange, held
+    /// with white on the bottom and blue in front.
+    pub const WESTERN: ColourScheme = ColourScheme {
+        top: Yellow,
+        left: Orange,
+        front: Blue,
+        right: Red,
+        back: Green,
+        bottom: White,
+    };
+
+    /// White opposite blue, yellow opposite green and red opposite orange, held
+    /// with white on the bottom and yellow in front.
+    pub const JAPANESE: ColourScheme = ColourScheme {
+        top: Blue,
+        left: Orange,
+        front: Yellow,
+        right: Red,
+        back: Green,
+        bottom: White,
+    };
+
+    /// The scheme given by a cube's centres.
+    pub fn of(cube: &Cube) -> ColourScheme {
+        ColourScheme {
+            top: cube.face_colour(Top),
+            left: cube.face_colour(Left),
+            front: cube.face_colour(Front),
+            right: cube.face_colour(Right),
+            back: cube.face_colour(Back),
+            bottom: cube.face_colour(Bottom),
+        }
+    }
+
+    /// The solved cube in this scheme.
+    pub const fn solved(&self) -> Cube {
+        Cube {
+            faces: [
+                Face([self.top; 9]),
+                Face([self.left; 9]),
+                Face([self.front; 9]),
+                Face([self.right; 9]),
+                Face([self.back; 9]),
+                Face([self.bottom; 9]),
+            ],
+        }
+    }
+
+    fn bottom_cross_edges(&self) -> [Edge; 4] {
+        [self.right, self.front, self.left, self.back].map(|side| Edge::new(self.bottom, side))
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum FaceName {
@@ -69,10 +125,49 @@ enum FaceName {
 
 use FaceName::*;
 
+impl FaceName {
+    /// The side face to the right of this one, when looking at it with the top
+    /// face up.
+    fn right_of(self) -> FaceName {
+        match self {
+            Front => Right,
+            Right => Back,
+            Back => Left,
+            Left => Front,
+            Top | Bottom => unreachable!(),
+        }
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 struct Edge(Colour, Colour);
 
-#[derive(Debug, PartialEq, Eq)]
+impl Edge {
+    /// Makes an edge with its colours sorted, so that edges compare equal
+    /// regardless of the order their stickers were read in.
+    fn new(a: Colour, b: Colour) -> Edge {
+        if a > b {
+            Edge(b, a)
+        } else {
+            Edge(a, b)
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Corner(Colour, Colour, Colour);
+
+impl Corner {
+    /// Makes a corner with its colours sorted, so that corners compare equal
+    /// regardless of the order their stickers were read in.
+    fn new(a: Colour, b: Colour, c: Colour) -> Corner {
+        let mut colours = [a, b, c];
+        colours.sort();
+        Corner(colours[0], colours[1], colours[2])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Face([Colour; 9]);
 
 impl Face {
@@ -132,7 +227,7 @@ X X X  X X X  X X X  X X X
        X X X
        X X X
 */
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Cube {
     // top, left, front, right, back, bottom
     faces: [Face; 6],
@@ -187,40 +282,8 @@ impl Display for Cube {
 }
 
 impl Cube {
-    // Old cube
-    /*
-    #[rustfmt::skip]
-    const SOLVED: Cube = Cube::make_cube([
-              /*Top*/
-              B, B, B,
-              B, B, B,
-              B, B, B,
-    /*Left    Front     Right     Back*/
-    R, R, R,  Y, Y, Y,  G, G, G,  O, O, O,
-    R, R, R,  Y, Y, Y,  G, G, G,  O, O, O,
-    R, R, R,  Y, Y, Y,  G, G, G,  O, O, O,
-              /*Bottom*/
-              W, W, W,
-              W, W, W,
-              W, W, W,
-    ]);
-    */
-    // New cube
-    #[rustfmt::skip]
-    pub const SOLVED: Cube = Cube::make_cube([
-              /*Top*/
-              Y, Y, Y,
-              Y, Y, Y,
-              Y, Y, Y,
-    /*Left    Front     Right     Back*/
-    O, O, O,  B, B, B,  R, R, R,  G, G, G,
-    O, O, O,  B, B, B,  R, R, R,  G, G, G,
-    O, O, O,  B, B, B,  R, R, R,  G, G, G,
-              /*Bottom*/
-              W, W, W,
-              W, W, W,
-              W, W, W,
-    ]);
+    /// The solved cube in the [`ColourScheme::WESTERN`] scheme.
+    pub const SOLVED: Cube = ColourScheme::WESTERN.solved();
 
     #[rustfmt::skip]
     pub const fn make_cube(colours: [Colour; 54]) -> Cube {
@@ -260,12 +323,58 @@ impl Cube {
         }
     }
 
+    /// Makes a cube like [`Cube::make_cube`], but checks that it can be
+    /// solved.
+    pub fn try_make_cube(colours: [Colour; 54]) -> Result<Cube, CubeError> {
+        let cube = Cube::make_cube(colours);
+        cube.validate()?;
+        Ok(cube)
+    }
+
+    /// Checks that the cube can be solved, reporting the first problem found.
+    pub fn validate(&self) -> Result<(), CubeError> {
+        for colour in Colour::ALL {
+            let count = self
+                .faces
+                .iter()
+                .flat_map(|face| face.0)
+                .filter(|&c| c == colour)
+                .count();
+            if count != 9 {
+                return Err(CubeError::ColourCount { colour, count });
+            }
+        }
+        for (i, face) in self.faces.iter().enumerate() {
+            if self.faces[i + 1..]
+                .iter()
+                .any(|f| f.colour() == face.colour())
+            {
+                return Err(CubeError::DuplicateCentre {
+                    colour: face.colour(),
+                });
+            }
+        }
+        cubie::CubieCube::from_cube(self)?.validate()
+    }
+
+    /// The positions of the edges that are misoriented relative to the front
+    /// and back faces, which can't be solved without quarter turns of those
+    /// faces.
+    pub fn misoriented_edges(&self) -> Result<Vec<cubie::Edge>, CubeError> {
+        self.validate()?;
+        let cube = cubie::CubieCube::from_cube(self)?;
+        Ok(cubie::EDGES
+            .into_iter()
+            .filter(|&edge| cube.eo[edge as usize] == 1)
+            .collect())
+    }
+
     fn face_colour(&self, face: FaceName) -> Colour {
         self[face].colour()
     }
 
     fn bottom_cross_edges(&self) -> [Edge; 4] {
-        EDGES[self.face_colour(Bottom) as usize]
+        ColourScheme::of(self).bottom_cross_edges()
     }
 
     fn edges(&self) -> impl Iterator<Item = (Edge, Pos)> {
@@ -293,11 +402,8 @@ impl Cube {
             result[11] = (Edge(self[Front].0[7], self[Bottom].0[1]), 25);
         }
 
-        // TODO: extract to e.g. Edge constructor so all edges are canonical.
-        for (Edge(a, b), _) in result.iter_mut() {
-            if *a > *b {
-                std::mem::swap(a, b);
-            }
+        for (edge, _) in result.iter_mut() {
+            *edge = Edge::new(edge.0, edge.1);
         }
 
         result.into_iter()
@@ -307,14 +413,117 @@ impl Cube {
         self.edges().find(|e| e.0 == edge).unwrap().1
     }
 
+    /// The bottom sticker of the edge at `pos`, which must be in the bottom
+    /// layer.
+    fn bottom_edge_colour(&self, pos: Pos) -> Colour {
+        match pos {
+            19 => self[Bottom].0[7],
+            21 => self[Bottom].0[3],
+            23 => self[Bottom].0[5],
+            25 => self[Bottom].0[1],
+            _ => unreachable!(),
+        }
+    }
+
+    fn bottom_corners(&self) -> [Corner; 4] {
+        let bottom = self.face_colour(Bottom);
+        let front = self.face_colour(Front);
+        let right = self.face_colour(Right);
+        let back = self.face_colour(Back);
+        let left = self.face_colour(Left);
+        [
+            Corner::new(bottom, front, right),
+            Corner::new(bottom, right, back),
+            Corner::new(bottom, back, left),
+            Corner::new(bottom, left, front),
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn corners(&self) -> impl Iterator<Item = (Corner, Pos)> {
+        [
+            // Top
+            (Corner::new(self[Top].0[0], self[Left].0[0], self[Back].0[2]), 0),
+            (Corner::new(self[Top].0[2], self[Back].0[0], self[Right].0[2]), 2),
+            (Corner::new(self[Top].0[6], self[Front].0[0], self[Left].0[2]), 6),
+            (Corner::new(self[Top].0[8], self[Right].0[0], self[Front].0[2]), 8),
+            // Bottom
+            (Corner::new(self[Bottom].0[6], self[Back].0[8], self[Left].0[6]), 18),
+            (Corner::new(self[Bottom].0[8], self[Right].0[8], self[Back].0[6]), 20),
+            (Corner::new(self[Bottom].0[0], self[Left].0[8], self[Front].0[6]), 24),
+            (Corner::new(self[Bottom].0[2], self[Front].0[8], self[Right].0[6]), 26),
+        ]
+        .into_iter()
+    }
+
+    fn find_corner(&self, corner: Corner) -> Pos {
+        self.corners().find(|c| c.0 == corner).unwrap().1
+    }
+
+    fn corner_at(&self, pos: Pos) -> Corner {
+        self.corners().find(|c| c.1 == pos).unwrap().0
+    }
+
+    /// The corner that belongs at `pos`, according to the centre colours.
+    fn home_corner(&self, pos: Pos) -> Corner {
+        let (a, b, c) = match pos {
+            0 => (Top, Left, Back),
+            2 => (Top, Back, Right),
+            6 => (Top, Front, Left),
+            8 => (Top, Right, Front),
+            18 => (Bottom, Back, Left),
+            20 => (Bottom, Right, Back),
+            24 => (Bottom, Left, Front),
+            26 => (Bottom, Front, Right),
+            _ => unreachable!(),
+        };
+        Corner::new(
+            self.face_colour(a),
+            self.face_colour(b),
+            self.face_colour(c),
+        )
+    }
+
+    /// The sticker of the corner at `pos` that is on the top or bottom face.
+    fn corner_vertical_colour(&self, pos: Pos) -> Colour {
+        match pos {
+            0 => self[Top].0[0],
+            2 => self[Top].0[2],
+            6 => self[Top].0[6],
+            8 => self[Top].0[8],
+            18 => self[Bottom].0[6],
+            20 => self[Bottom].0[8],
+            24 => self[Bottom].0[0],
+            26 => self[Bottom].0[2],
+            _ => unreachable!(),
+        }
+    }
+
+    fn is_solved(&self) -> bool {
+        self.faces
+            .iter()
+            .all(|face| face.0.iter().all(|&c| c == face.colour()))
+    }
+
     pub fn make_move(&mut self, action: Move) {
+        if let Some(parts) = action.parts() {
+            for &part in parts {
+                self.make_move(part);
+            }
+            return;
+        }
+
         /// Side slices are specified: ["Top", "Right", "Bottom", "Left"], relative to the face-to-be-moved.
         /// For clockwise, slices are rotated: top->right->bottom->left->top
         /// For anti-clockwise, slices are rotated: top->left->bottom->right->top
+        /// Half turns are made as two clockwise turns.
+        /// Slice turns have no face of their own to rotate.
+        /// Each slice lists its stickers in the order they travel, so that the
+        /// n-th sticker of one slice always lands on the n-th sticker of the next.
         macro_rules! rotate_sides {
             (
                 $([
-                    $clock:ident, $anti_clock:ident, $face:ident,
+                    $clock:ident, $anti_clock:ident, $half:ident, [$($face:ident)?],
                     [$top:ident, $t1:literal, $t2:literal, $t3:literal],
                     [$right:ident, $r1:literal, $r2:literal, $r3:literal],
                     [$bottom:ident, $b1:literal, $b2:literal, $b3:literal],
@@ -346,7 +555,7 @@ impl Cube {
                             self[$top].0[$t2] = left[1];
                             self[$top].0[$t3] = left[2];
 
-                            self[$face].rotate(true);
+                            $(self[$face].rotate(true);)?
                         }
                         Move::$anti_clock => {
                             let top = [self[$top].0[$t1], self[$top].0[$t2], self[$top].0[$t3]];
@@ -355,25 +564,30 @@ impl Cube {
                             let left = [self[$left].0[$l1], self[$left].0[$l2], self[$left].0[$l3]];
 
                             // top->left
-                            self[$left].0[$l1] = top[2];
+                            self[$left].0[$l1] = top[0];
                             self[$left].0[$l2] = top[1];
-                            self[$left].0[$l3] = top[0];
+                            self[$left].0[$l3] = top[2];
                             // left->bottom
                             self[$bottom].0[$b1] = left[0];
                             self[$bottom].0[$b2] = left[1];
                             self[$bottom].0[$b3] = left[2];
                             // bottom->right
-                            self[$right].0[$r1] = bottom[2];
+                            self[$right].0[$r1] = bottom[0];
                             self[$right].0[$r2] = bottom[1];
-                            self[$right].0[$r3] = bottom[0];
+                            self[$right].0[$r3] = bottom[2];
                             // right->top
                             self[$top].0[$t1] = right[0];
                             self[$top].0[$t2] = right[1];
                             self[$top].0[$t3] = right[2];
 
-                            self[$face].rotate(false);
+                            $(self[$face].rotate(false);)?
+                        }
+                        Move::$half => {
+                            self.make_move(Move::$clock);
+                            self.make_move(Move::$clock);
                         }
                     )+
+                    _ => unreachable!("{:?} is made from its parts", action),
                 }
             };
         }
@@ -381,46 +595,67 @@ impl Cube {
         #[rustfmt::skip]
         rotate_sides!(
             [
-                F, FP, Front,
+                F, FP, F2, [Front],
                 [Top, 6, 7, 8],
                 [Right, 0, 3, 6],
-                [Bottom, 0, 1, 2],
-                [Left, 2, 5, 8],
+                [Bottom, 2, 1, 0],
+                [Left, 8, 5, 2],
             ],
             [
-                B, BP, Back,
-                [Top, 0, 1, 2],
+                B, BP, B2, [Back],
+                [Top, 2, 1, 0],
                 [Left, 0, 3, 6],
                 [Bottom, 6, 7, 8],
-                [Right, 2, 5, 8],
+                [Right, 8, 5, 2],
             ],
             [
-                U, UP, Top,
+                U, UP, U2, [Top],
                 [Back, 0, 1, 2],
                 [Right, 0, 1, 2],
                 [Front, 0, 1, 2],
                 [Left, 0, 1, 2],
             ],
             [
-                D, DP, Bottom,
+                D, DP, D2, [Bottom],
                 [Front, 6, 7, 8],
                 [Right, 6, 7, 8],
                 [Back, 6, 7, 8],
                 [Left, 6, 7, 8],
             ],
             [
-                L, LP, Left,
+                L, LP, L2, [Left],
                 [Top, 0, 3, 6],
                 [Front, 0, 3, 6],
                 [Bottom, 0, 3, 6],
-                [Back, 2, 5, 8],
+                [Back, 8, 5, 2],
             ],
             [
-                R, RP, Right,
+                R, RP, R2, [Right],
                 [Top, 8, 5, 2],
                 [Back, 0, 3, 6],
-                [Bottom, 2, 5, 8],
-                [Front, 2, 5, 8],
+                [Bottom, 8, 5, 2],
+                [Front, 8, 5, 2],
+            ],
+            [
+                M, MP, M2, [],
+                [Top, 1, 4, 7],
+                [Front, 1, 4, 7],
+                [Bottom, 1, 4, 7],
+                [Back, 7, 4, 1],
+            ],
+            [
+                E, EP, E2, [],
+                [Front, 3, 4, 5],
+                [Right, 3, 4, 5],
+                [Back, 3, 4, 5],
+                [Left, 3, 4, 5],
+            ],
+            [
+                S, SP, S2, [],
+                [Top, 3, 4, 5],
+                [Right, 1, 4, 7],
+                [Bottom, 5, 4, 3],
+                [Left, 7, 4, 1],
             ],
         );
     }
@@ -440,7 +675,129 @@ impl IndexMut<FaceName> for Cube {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Why a cube can't be solved. Pieces and positions are named after the faces
+/// they touch, relative to the centres.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CubeError {
+    /// A colour isn't on exactly nine stickers.
+    ColourCount { colour: Colour, count: usize },
+    /// Two centres have the same colour.
+    DuplicateCentre { colour: Colour },
+    /// The stickers at a corner position don't make up any corner.
+    ImpossibleCorner {
+        position: cubie::Corner,
+        colours: [Colour; 3],
+    },
+    /// The stickers at an edge position don't make up any edge.
+    ImpossibleEdge {
+        position: cubie::Edge,
+        colours: [Colour; 2],
+    },
+    /// A corner is in two positions at once.
+    DuplicateCorner {
+        corner: cubie::Corner,
+        positions: [cubie::Corner; 2],
+    },
+    /// An edge is in two positions at once.
+    DuplicateEdge {
+        edge: cubie::Edge,
+        positions: [cubie::Edge; 2],
+    },
+    /// The corners are twisted by `twist` thirds of a clockwise turn in total,
+    /// as if a single corner had been twisted in place.
+    CornerTwist { twist: u8 },
+    /// An odd number of edges are flipped, as if a single edge had been
+    /// flipped in place.
+    EdgeFlip,
+    /// The corner and edge permutations have different parities, as if two
+    /// pieces had been swapped.
+    PermutationParity,
+}
+
+impl Display for CubeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = |piece: &dyn std::fmt::Debug| format!("{:?}", piece).to_uppercase();
+        match self {
+            CubeError::ColourCount { colour, count } => {
+                write!(f, "{} is on {} stickers instead of 9", colour, count)
+            }
+            CubeError::DuplicateCentre { colour } => {
+                write!(f, "{} is the colour of more than one centre", colour)
+            }
+            CubeError::ImpossibleCorner { position, colours } => write!(
+                f,
+                "the corner at {} has colours {}, {} and {}, which no corner has",
+                name(position),
+                colours[0],
+                colours[1],
+                colours[2]
+            ),
+            CubeError::ImpossibleEdge { position, colours } => write!(
+                f,
+                "the edge at {} has colours {} and {}, which no edge has",
+                name(position),
+                colours[0],
+                colours[1]
+            ),
+            CubeError::DuplicateCorner { corner, positions } => write!(
+                f,
+                "the {} corner is at both {} and {}",
+                name(corner),
+                name(&positions[0]),
+                name(&positions[1])
+            ),
+            CubeError::DuplicateEdge { edge, positions } => write!(
+                f,
+                "the {} edge is at both {} and {}",
+                name(edge),
+                name(&positions[0]),
+                name(&positions[1])
+            ),
+            CubeError::CornerTwist { twist } => write!(
+                f,
+                "the corners are twisted by {} of a turn in total",
+                if *twist == 1 { "a third" } else { "two thirds" }
+            ),
+            CubeError::EdgeFlip => f.write_str("an odd number of edges are flipped"),
+            CubeError::PermutationParity => {
+                f.write_str("the corner and edge permutations have different parities")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CubeError {}
+
+/// Why a solver gave up on a cube that can be solved.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolveError {
+    /// The search visited `nodes` positions without finding a solution.
+    OutOfNodes { nodes: u64 },
+    /// A stage of a method needed more than `max_length` moves.
+    StageTooLong {
+        stage: &'static str,
+        max_length: usize,
+    },
+}
+
+impl Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::OutOfNodes { nodes } => write!(
+                f,
+                "the search gave up after {} positions without a solution",
+                nodes
+            ),
+            SolveError::StageTooLong { stage, max_length } => {
+                write!(f, "the {} takes more than {} moves", stage, max_length)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Move {
     F,
     B,
@@ -455,6 +812,63 @@ pub enum Move {
     DP,
     LP,
     RP,
+
+    F2,
+    B2,
+    U2,
+    D2,
+    L2,
+    R2,
+
+    // Slice turns of the middle layers, which turn the same way as L, D and F
+    // respectively.
+    M,
+    E,
+    S,
+
+    MP,
+    EP,
+    SP,
+
+    M2,
+    E2,
+    S2,
+
+    // Wide turns, of a face and the middle layer next to it together.
+    Fw,
+    Bw,
+    Uw,
+    Dw,
+    Lw,
+    Rw,
+
+    FwP,
+    BwP,
+    UwP,
+    DwP,
+    LwP,
+    RwP,
+
+    Fw2,
+    Bw2,
+    Uw2,
+    Dw2,
+    Lw2,
+    Rw2,
+
+    // Rotations of the whole cube, which turn the same way as R, U and F
+    // respectively.
+    X,
+    Y,
+    Z,
+
+    XP,
+    YP,
+    ZP,
+
+    X2,
+    Y2,
+    Z2,
 }
 
 impl Move {
@@ -473,8 +887,88 @@ impl Move {
             Self::DP => Self::D,
             Self::LP => Self::L,
             Self::RP => Self::R,
+
+            Self::F2 => Self::F2,
+            Self::B2 => Self::B2,
+            Self::U2 => Self::U2,
+            Self::D2 => Self::D2,
+            Self::L2 => Self::L2,
+            Self::R2 => Self::R2,
+
+            Self::M => Self::MP,
+            Self::E => Self::EP,
+            Self::S => Self::SP,
+            Self::MP => Self::M,
+            Self::EP => Self::E,
+            Self::SP => Self::S,
+            Self::M2 => Self::M2,
+            Self::E2 => Self::E2,
+            Self::S2 => Self::S2,
+
+            Self::Fw => Self::FwP,
+            Self::Bw => Self::BwP,
+            Self::Uw => Self::UwP,
+            Self::Dw => Self::DwP,
+            Self::Lw => Self::LwP,
+            Self::Rw => Self::RwP,
+            Self::FwP => Self::Fw,
+            Self::BwP => Self::Bw,
+            Self::UwP => Self::Uw,
+            Self::DwP => Self::Dw,
+            Self::LwP => Self::Lw,
+            Self::RwP => Self::Rw,
+            Self::Fw2 => Self::Fw2,
+            Self::Bw2 => Self::Bw2,
+            Self::Uw2 => Self::Uw2,
+            Self::Dw2 => Self::Dw2,
+            Self::Lw2 => Self::Lw2,
+            Self::Rw2 => Self::Rw2,
+
+            Self::X => Self::XP,
+            Self::Y => Self::YP,
+            Self::Z => Self::ZP,
+            Self::XP => Self::X,
+            Self::YP => Self::Y,
+            Self::ZP => Self::Z,
+            Self::X2 => Self::X2,
+            Self::Y2 => Self::Y2,
+            Self::Z2 => Self::Z2,
         }
     }
+
+    /// The layer turns that make up a wide turn or a rotation.
+    fn parts(self) -> Option<&'static [Move]> {
+        Some(match self {
+            Self::Fw => &[Self::F, Self::S],
+            Self::Bw => &[Self::B, Self::SP],
+            Self::Uw => &[Self::U, Self::EP],
+            Self::Dw => &[Self::D, Self::E],
+            Self::Lw => &[Self::L, Self::M],
+            Self::Rw => &[Self::R, Self::MP],
+            Self::FwP => &[Self::FP, Self::SP],
+            Self::BwP => &[Self::BP, Self::S],
+            Self::UwP => &[Self::UP, Self::E],
+            Self::DwP => &[Self::DP, Self::EP],
+            Self::LwP => &[Self::LP, Self::MP],
+            Self::RwP => &[Self::RP, Self::M],
+            Self::Fw2 => &[Self::F2, Self::S2],
+            Self::Bw2 => &[Self::B2, Self::S2],
+            Self::Uw2 => &[Self::U2, Self::E2],
+            Self::Dw2 => &[Self::D2, Self::E2],
+            Self::Lw2 => &[Self::L2, Self::M2],
+            Self::Rw2 => &[Self::R2, Self::M2],
+            Self::X => &[Self::R, Self::MP, Self::LP],
+            Self::Y => &[Self::U, Self::EP, Self::DP],
+            Self::Z => &[Self::F, Self::S, Self::BP],
+            Self::XP => &[Self::RP, Self::M, Self::L],
+            Self::YP => &[Self::UP, Self::E, Self::D],
+            Self::ZP => &[Self::FP, Self::SP, Self::B],
+            Self::X2 => &[Self::R2, Self::M2, Self::L2],
+            Self::Y2 => &[Self::U2, Self::E2, Self::D2],
+            Self::Z2 => &[Self::F2, Self::S2, Self::B2],
+            _ => return None,
+        })
+    }
 }
 
 const fn make_identity_map() -> [u8; 54] {
@@ -489,11 +983,18 @@ const fn make_identity_map() -> [u8; 54] {
 const POS_IDENTITY_MAP: [u8; 54] = make_identity_map();
 
 fn update_pos_after_move(pos: &mut Pos, action: Move) {
+    if let Some(parts) = action.parts() {
+        for &part in parts {
+            update_pos_after_move(pos, part);
+        }
+        return;
+    }
+
     macro_rules! make_maps {
         (
             $(
                 [
-                    $move:ident, $anti_move:ident,
+                    $move:ident, $anti_move:ident, $half:ident,
                     [$($s:literal=>$t:literal,)+]
                 ],
             )+
@@ -515,14 +1016,19 @@ fn update_pos_after_move(pos: &mut Pos, action: Move) {
                         static MAP: [u8; 54] = {
                             const fn mk() -> [u8; 54]{
                                 let mut map = POS_IDENTITY_MAP;
-                                $(map[$s] = $t;)+
+                                $(map[$t] = $s;)+
                                 map
                             }
                             mk()
                         };
                         *pos = MAP[*pos as usize];
                     }
+                    Move::$half => {
+                        update_pos_after_move(pos, Move::$move);
+                        update_pos_after_move(pos, Move::$move);
+                    }
                 )+
+                _ => unreachable!("{:?} is made from its parts", action),
             }
 
 
@@ -531,33 +1037,33 @@ fn update_pos_after_move(pos: &mut Pos, action: Move) {
     #[rustfmt::skip]
     make_maps!(
         [
-            F, FP,
+            F, FP, F2,
             [
                 6 => 8,
-                7 => 16,
+                7 => 17,
                 8 => 26,
                 15 => 7,
-                16 => 25,
+                17 => 25,
                 24 => 6,
                 25 => 15,
                 26 => 24,
             ]
         ],
         [
-            B, BP,
+            B, BP, B2,
             [
-                0 => 2,
-                1 => 11,
-                2 => 20,
-                9 => 1,
-                11 => 19,
-                18 => 0,
-                19 => 9,
-                20 => 18,
+                0 => 18,
+                1 => 9,
+                2 => 0,
+                9 => 19,
+                11 => 1,
+                18 => 20,
+                19 => 11,
+                20 => 2,
             ]
         ],
         [
-            U, UP,
+            U, UP, U2,
             [
                 0 => 2,
                 1 => 5,
@@ -570,20 +1076,20 @@ fn update_pos_after_move(pos: &mut Pos, action: Move) {
             ]
         ],
         [
-            D, DP,
+            D, DP, D2,
             [
-                18 => 20,
-                19 => 23,
-                20 => 26,
-                21 => 19,
-                23 => 25,
-                24 => 18,
-                25 => 21,
-                26 => 24,
+                18 => 24,
+                19 => 21,
+                20 => 18,
+                21 => 25,
+                23 => 19,
+                24 => 26,
+                25 => 23,
+                26 => 20,
             ]
         ],
         [
-            L, LP,
+            L, LP, L2,
             [
                 0 => 6,
                 3 => 15,
@@ -596,7 +1102,7 @@ fn update_pos_after_move(pos: &mut Pos, action: Move) {
             ]
         ],
         [
-            R, RP,
+            R, RP, R2,
             [
                 2 => 20,
                 5 => 11,
@@ -608,11 +1114,51 @@ fn update_pos_after_move(pos: &mut Pos, action: Move) {
                 26 => 8,
             ]
         ],
+        [
+            M, MP, M2,
+            [
+                1 => 7,
+                4 => 16,
+                7 => 25,
+                10 => 4,
+                16 => 22,
+                19 => 1,
+                22 => 10,
+                25 => 19,
+            ]
+        ],
+        [
+            E, EP, E2,
+            [
+                9 => 15,
+                10 => 12,
+                11 => 9,
+                12 => 16,
+                14 => 10,
+                15 => 17,
+                16 => 14,
+                17 => 11,
+            ]
+        ],
+        [
+            S, SP, S2,
+            [
+                3 => 5,
+                4 => 14,
+                5 => 23,
+                12 => 4,
+                14 => 22,
+                21 => 3,
+                22 => 12,
+                23 => 21,
+            ]
+        ],
     );
 }
 
 struct Solver {
     cube: Cube,
+    scheme: ColourScheme,
     move_stack: Vec<Move>,
 }
 
@@ -622,6 +1168,27 @@ impl Solver {
         self.move_stack.push(action);
     }
 
+    fn make_moves(&mut self, actions: &[Move]) {
+        for &action in actions {
+            self.make_move(action);
+        }
+    }
+
+    /// Makes the moves as if the cube were held with `front` facing the solver.
+    fn make_relative_moves(&mut self, front: FaceName, actions: &[Move]) {
+        for &action in actions {
+            self.make_move(relative_move(front, action));
+        }
+    }
+
+    /// Makes the moves, keeping `pos` pointing at the same piece.
+    fn make_tracked_moves(&mut self, actions: &[Move], pos: &mut Pos) {
+        for &action in actions {
+            self.make_move(action);
+            update_pos_after_move(pos, action);
+        }
+    }
+
     fn move_top_edge(&mut self, source: u8, target: u8) {
         let mut num_moves = target as i32 - source as i32;
 
@@ -631,10 +1198,11 @@ impl Solver {
             num_moves = 1;
         }
 
-        let action = if num_moves < 0 { Move::UP } else { Move::U };
-
-        for _ in 0..i32::abs(num_moves) {
-            self.make_move(action);
+        match num_moves {
+            0 => {}
+            1 => self.make_move(Move::U),
+            -1 => self.make_move(Move::UP),
+            _ => self.make_move(Move::U2),
         }
     }
 
@@ -658,8 +1226,8 @@ impl Solver {
                             update_pos_after_move(&mut source, Move::RP);
                         }
                         15 => {
-                            self.make_move(Move::L);
-                            update_pos_after_move(&mut source, Move::L);
+                            self.make_move(Move::LP);
+                            update_pos_after_move(&mut source, Move::LP);
                         }
                         17 => {
                             self.make_move(Move::R);
@@ -672,28 +1240,20 @@ impl Solver {
                     // Bottom layer
                     match source {
                         19 => {
-                            self.make_move(Move::B);
-                            update_pos_after_move(&mut source, Move::B);
-                            self.make_move(Move::B);
-                            update_pos_after_move(&mut source, Move::B);
+                            self.make_move(Move::B2);
+                            update_pos_after_move(&mut source, Move::B2);
                         }
                         21 => {
-                            self.make_move(Move::L);
-                            update_pos_after_move(&mut source, Move::L);
-                            self.make_move(Move::L);
-                            update_pos_after_move(&mut source, Move::L);
+                            self.make_move(Move::L2);
+                            update_pos_after_move(&mut source, Move::L2);
                         }
                         23 => {
-                            self.make_move(Move::R);
-                            update_pos_after_move(&mut source, Move::R);
-                            self.make_move(Move::R);
-                            update_pos_after_move(&mut source, Move::R);
+                            self.make_move(Move::R2);
+                            update_pos_after_move(&mut source, Move::R2);
                         }
                         25 => {
-                            self.make_move(Move::F);
-                            update_pos_after_move(&mut source, Move::F);
-                            self.make_move(Move::F);
-                            update_pos_after_move(&mut source, Move::F);
+                            self.make_move(Move::F2);
+                            update_pos_after_move(&mut source, Move::F2);
                         }
                         _ => unreachable!(),
                     }
@@ -712,14 +1272,9 @@ impl Solver {
             // Restore sides.
             match cur_layer {
                 0 => {}
-                1 => {
-                    let m = self.move_stack[self.move_stack.len() - 2];
-                    self.make_move(m.reverse());
-                }
-                2 => {
+                1 | 2 => {
                     let m = self.move_stack[self.move_stack.len() - 2];
                     self.make_move(m.reverse());
-                    self.make_move(m.reverse());
                 }
                 _ => unreachable!(),
             }
@@ -738,10 +1293,7 @@ impl Solver {
             3 => 3,
             5 => 1,
             7 => 2,
-            _ => {
-                println!("{}", self.cube);
-                unreachable!();
-            }
+            _ => unreachable!(),
         };
 
         if self.cube[Top].0[top_face_pos as usize] == self.cube.face_colour(Bottom) {
@@ -749,13 +1301,13 @@ impl Solver {
 
             let (target_edge_idx, target_face_move) =
                 if non_bottom_colour == self.cube.face_colour(Back) {
-                    (0, Move::B)
+                    (0, Move::B2)
                 } else if non_bottom_colour == self.cube.face_colour(Right) {
-                    (1, Move::R)
+                    (1, Move::R2)
                 } else if non_bottom_colour == self.cube.face_colour(Front) {
-                    (2, Move::F)
+                    (2, Move::F2)
                 } else if non_bottom_colour == self.cube.face_colour(Left) {
-                    (3, Move::L)
+                    (3, Move::L2)
                 } else {
                     unreachable!();
                 };
@@ -763,7 +1315,6 @@ impl Solver {
             self.move_top_edge(cur_edge_idx, target_edge_idx);
 
             self.make_move(target_face_move);
-            self.make_move(target_face_move);
         } else {
             // Bottom colour is facing out on the edge.
 
@@ -794,26 +1345,486 @@ impl Solver {
         }
     }
 
+    /// Rotates the cube so that its centres are where they are in the
+    /// solver's colour scheme, which the other stages rely on.
+    fn normalise_orientation(&mut self) {
+        let rotations = rotations_to(&self.cube, self.scheme)
+            .expect("centres can't be rotated to match the colour scheme");
+        self.make_moves(&rotations);
+    }
+
     fn solve_bottom_cross(&mut self) {
+        let bottom = self.cube.face_colour(Bottom);
         for target_edge in self.cube.bottom_cross_edges() {
             let cur_pos = self.cube.find_edge(target_edge);
-            let target_pos = Cube::SOLVED.find_edge(target_edge);
-            if cur_pos == target_pos {
+            let target_pos = self.scheme.solved().find_edge(target_edge);
+            if cur_pos == target_pos && self.cube.bottom_edge_colour(cur_pos) == bottom {
                 continue;
             }
             self.move_bottom_cross_edge(cur_pos, target_edge);
         }
     }
+
+    fn solve_bottom_corners(&mut self) {
+        let bottom = self.cube.face_colour(Bottom);
+        for target_corner in self.cube.bottom_corners() {
+            let target_pos = self.scheme.solved().find_corner(target_corner);
+            let mut cur_pos = self.cube.find_corner(target_corner);
+            if cur_pos == target_pos && self.cube.corner_vertical_colour(cur_pos) == bottom {
+                continue;
+            }
+
+            // Lift the corner out of the wrong bottom slot into the top layer.
+            if cur_pos / 9 == 2 {
+                let lift = corner_slot_move(cur_pos);
+                self.make_tracked_moves(&[lift, Move::U, lift.reverse()], &mut cur_pos);
+            }
+
+            // Spin the top so the corner sits directly above its slot.
+            while cur_pos != target_pos - 18 {
+                self.make_tracked_moves(&[Move::U], &mut cur_pos);
+            }
+
+            // Each repetition swaps the corner between the slot and the top
+            // layer, twisting it as it goes, so it drops in correctly oriented
+            // within six repetitions.
+            let lift = corner_slot_move(target_pos);
+            while self.cube.corner_at(target_pos) != target_corner
+                || self.cube.corner_vertical_colour(target_pos) != bottom
+            {
+                self.make_moves(&[lift, Move::U, lift.reverse(), Move::UP]);
+            }
+        }
+    }
+
+    fn solve_middle_edges(&mut self) {
+        // Insert the edge at the front of the top layer into the middle slot
+        // to its right/left.
+        const RIGHT_INSERT: [Move; 8] = [
+            Move::U,
+            Move::R,
+            Move::UP,
+            Move::RP,
+            Move::UP,
+            Move::FP,
+            Move::U,
+            Move::F,
+        ];
+        const LEFT_INSERT: [Move; 8] = [
+            Move::UP,
+            Move::LP,
+            Move::U,
+            Move::L,
+            Move::U,
+            Move::F,
+            Move::UP,
+            Move::FP,
+        ];
+
+        for front in [Front, Right, Back, Left] {
+            let right = front.right_of();
+            let target_edge = Edge::new(self.cube.face_colour(front), self.cube.face_colour(right));
+            let target_pos = middle_slot(front);
+            let mut cur_pos = self.cube.find_edge(target_edge);
+            if cur_pos == target_pos && self.cube[front].0[5] == self.cube.face_colour(front) {
+                continue;
+            }
+
+            // Knock the edge out of the wrong slot into the top layer.
+            if cur_pos / 9 == 1 {
+                let slot_front = [Front, Right, Back, Left]
+                    .into_iter()
+                    .find(|&face| middle_slot(face) == cur_pos)
+                    .unwrap();
+                for action in RIGHT_INSERT {
+                    let action = relative_move(slot_front, action);
+                    self.make_move(action);
+                    update_pos_after_move(&mut cur_pos, action);
+                }
+            }
+
+            // Spin the top until the edge's side sticker matches the centre
+            // below it.
+            let side_colour = self.cube[top_edge_face(cur_pos)].0[1];
+            while self.cube.face_colour(top_edge_face(cur_pos)) != side_colour {
+                self.make_tracked_moves(&[Move::U], &mut cur_pos);
+            }
+
+            let face = top_edge_face(cur_pos);
+            let top_colour = self.cube[Top].0[cur_pos as usize];
+            if top_colour == self.cube.face_colour(face.right_of()) {
+                self.make_relative_moves(face, &RIGHT_INSERT);
+            } else {
+                self.make_relative_moves(face, &LEFT_INSERT);
+            }
+        }
+    }
+
+    fn solve_top_cross(&mut self) {
+        const FLIP: [Move; 6] = [Move::F, Move::R, Move::U, Move::RP, Move::UP, Move::FP];
+
+        let top = self.cube.face_colour(Top);
+        loop {
+            let oriented = |pos: usize| self.cube[Top].0[pos] == top;
+            let front = match (oriented(1), oriented(3), oriented(5), oriented(7)) {
+                (true, true, true, true) => break,
+                // Lines are flipped from the side, so that they run left to right.
+                (false, true, true, false) => Front,
+                (true, false, false, true) => Left,
+                // L-shapes are flipped with the L in the back left.
+                (true, true, false, false) => Front,
+                (true, false, true, false) => Left,
+                (false, false, true, true) => Back,
+                (false, true, false, true) => Right,
+                _ => Front,
+            };
+            self.make_relative_moves(front, &FLIP);
+        }
+    }
+
+    fn orient_top_corners(&mut self) {
+        // Twists the front right corner of the top layer clockwise. The
+        // bottom layers are messed up in the process, but are restored once
+        // every top corner has been twisted, since the twists always add up
+        // to whole turns.
+        const TWIST: [Move; 8] = [
+            Move::RP,
+            Move::DP,
+            Move::R,
+            Move::D,
+            Move::RP,
+            Move::DP,
+            Move::R,
+            Move::D,
+        ];
+
+        let top = self.cube.face_colour(Top);
+        if [0, 2, 6, 8].iter().all(|&pos| self.cube[Top].0[pos] == top) {
+            return;
+        }
+        for _ in 0..4 {
+            while self.cube[Top].0[8] != top {
+                self.make_moves(&TWIST);
+            }
+            self.make_move(Move::U);
+        }
+    }
+
+    fn permute_top_corners(&mut self) {
+        #[rustfmt::skip]
+        const A_PERMS: [[Move; 9]; 2] = [
+            // Aa
+            [Move::RP, Move::F, Move::RP, Move::B2, Move::R, Move::FP, Move::RP, Move::B2, Move::R2],
+            // Ab
+            [Move::R2, Move::B2, Move::R, Move::F, Move::RP, Move::B2, Move::R, Move::FP, Move::R],
+        ];
+
+        loop {
+            if let Some(turns) = top_corners_alignment(&self.cube) {
+                for _ in 0..turns {
+                    self.make_move(Move::U);
+                }
+                return;
+            }
+
+            // Each A-perm cycles three corners. If none of them leave the
+            // corners solved (up to a turn of the top), the corners need a
+            // double swap, which any A-perm turns into a single cycle.
+            let (front, perm) = [Front, Right, Back, Left]
+                .into_iter()
+                .flat_map(|front| A_PERMS.iter().map(move |perm| (front, perm)))
+                .find(|&(front, perm)| {
+                    let mut cube = self.cube.clone();
+                    for &action in perm {
+                        cube.make_move(relative_move(front, action));
+                    }
+                    top_corners_alignment(&cube).is_some()
+                })
+                .unwrap_or((Front, &A_PERMS[0]));
+            self.make_relative_moves(front, perm);
+        }
+    }
+
+    fn permute_top_edges(&mut self) {
+        #[rustfmt::skip]
+        const U_PERMS: [[Move; 11]; 2] = [
+            // Ua
+            [Move::R, Move::UP, Move::R, Move::U, Move::R, Move::U, Move::R, Move::UP, Move::RP, Move::UP, Move::R2],
+            // Ub
+            [Move::R2, Move::U, Move::R, Move::U, Move::RP, Move::UP, Move::RP, Move::UP, Move::RP, Move::U, Move::RP],
+        ];
+
+        while !self.cube.is_solved() {
+            // Each U-perm cycles three edges. If none of them solve the cube,
+            // the edges need a double swap, which any U-perm turns into a
+            // single cycle.
+            let (front, perm) = [Front, Right, Back, Left]
+                .into_iter()
+                .flat_map(|front| U_PERMS.iter().map(move |perm| (front, perm)))
+                .find(|&(front, perm)| {
+                    let mut cube = self.cube.clone();
+                    for &action in perm {
+                        cube.make_move(relative_move(front, action));
+                    }
+                    cube.is_solved()
+                })
+                .unwrap_or((Front, &U_PERMS[0]));
+            self.make_relative_moves(front, perm);
+        }
+    }
+}
+
+/// The face turned to lift the corner in the given bottom slot into the top
+/// layer, directly above the slot.
+fn corner_slot_move(pos: Pos) -> Move {
+    match pos {
+        18 => Move::L,
+        20 => Move::B,
+        24 => Move::F,
+        26 => Move::R,
+        _ => unreachable!(),
+    }
+}
+
+/// The middle slot between `front` and the face to its right.
+fn middle_slot(front: FaceName) -> Pos {
+    match front {
+        Front => 17,
+        Right => 11,
+        Back => 9,
+        Left => 15,
+        _ => unreachable!(),
+    }
+}
+
+/// The side face that the edge at the given top layer position touches.
+fn top_edge_face(pos: Pos) -> FaceName {
+    match pos {
+        1 => Back,
+        3 => Left,
+        5 => Right,
+        7 => Front,
+        _ => unreachable!(),
+    }
+}
+
+/// The number of clockwise turns of the top layer that would leave every top
+/// corner in its solved position, if any.
+fn top_corners_alignment(cube: &Cube) -> Option<usize> {
+    let mut cube = cube.clone();
+    for turns in 0..4 {
+        if [0, 2, 6, 8]
+            .into_iter()
+            .all(|pos| cube.corner_at(pos) == cube.home_corner(pos))
+        {
+            return Some(turns);
+        }
+        cube.make_move(Move::U);
+    }
+    None
+}
+
+/// Maps a move, given as if `front` were facing the solver, onto the face that
+/// actually needs to be turned.
+fn relative_move(front: FaceName, action: Move) -> Move {
+    let quarter_turns = match front {
+        Front => 0,
+        Right => 1,
+        Back => 2,
+        Left => 3,
+        _ => unreachable!(),
+    };
+    let mut action = action;
+    for _ in 0..quarter_turns {
+        action = match action {
+            Move::F => Move::R,
+            Move::R => Move::B,
+            Move::B => Move::L,
+            Move::L => Move::F,
+            Move::FP => Move::RP,
+            Move::RP => Move::BP,
+            Move::BP => Move::LP,
+            Move::LP => Move::FP,
+            Move::F2 => Move::R2,
+            Move::R2 => Move::B2,
+            Move::B2 => Move::L2,
+            Move::L2 => Move::F2,
+            Move::M => Move::S,
+            Move::S => Move::MP,
+            Move::MP => Move::SP,
+            Move::SP => Move::M,
+            Move::M2 => Move::S2,
+            Move::S2 => Move::M2,
+            Move::Fw => Move::Rw,
+            Move::Rw => Move::Bw,
+            Move::Bw => Move::Lw,
+            Move::Lw => Move::Fw,
+            Move::FwP => Move::RwP,
+            Move::RwP => Move::BwP,
+            Move::BwP => Move::LwP,
+            Move::LwP => Move::FwP,
+            Move::Fw2 => Move::Rw2,
+            Move::Rw2 => Move::Bw2,
+            Move::Bw2 => Move::Lw2,
+            Move::Lw2 => Move::Fw2,
+            Move::X => Move::ZP,
+            Move::Z => Move::X,
+            Move::XP => Move::Z,
+            Move::ZP => Move::XP,
+            Move::X2 => Move::Z2,
+            Move::Z2 => Move::X2,
+            other => other,
+        };
+    }
+    action
 }
 
+/// The rotations that bring each face to the bottom: the bottom, back, top,
+/// front, right and left faces.
+const BOTTOM_ROTATIONS: [&[Move]; 6] = [
+    &[],
+    &[Move::X],
+    &[Move::X2],
+    &[Move::XP],
+    &[Move::Z],
+    &[Move::ZP],
+];
+
+/// The rotations that keep the bottom face where it is.
+const FRONT_ROTATIONS: [&[Move]; 4] = [&[], &[Move::Y], &[Move::Y2], &[Move::YP]];
+
+/// The whole cube rotations that bring the cube's centres to where they are in
+/// `scheme`, if there are any.
+fn rotations_to(cube: &Cube, scheme: ColourScheme) -> Option<Vec<Move>> {
+    BOTTOM_ROTATIONS
+        .into_iter()
+        .flat_map(|bottom| FRONT_ROTATIONS.map(|front| [bottom, front].concat()))
+        .find(|rotations| {
+            let mut cube = cube.clone();
+            for &rotation in rotations {
+                cube.make_move(rotation);
+            }
+            ColourScheme::of(&cube) == scheme
+        })
+}
+
+/// A named part of a solution, for methods that solve the cube in stages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stage {
+    pub name: &'static str,
+    pub moves: Vec<Move>,
+}
+
+/// The algorithms that can be used to solve a cube.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Method {
+    /// The beginner's method, solving one layer at a time. Simple to follow,
+    /// but gives long solutions.
+    LayerByLayer,
+    /// Kociemba's two-phase algorithm, giving solutions of about 20 moves.
+    TwoPhase,
+    /// A search for the shortest possible solution. Cubes far from solved can
+    /// take hours; see [`optimal`].
+    Optimal,
+    /// Thistlethwaite's algorithm, which solves the cube in four phases, each
+    /// using fewer kinds of moves than the last.
+    Thistlethwaite,
+    /// The CFOP speedsolving method: the cross, the first two layers a pair
+    /// at a time, then the last layer with one algorithm to orient it and one
+    /// to permute it.
+    Cfop,
+    /// The Roux speedsolving method: a block on each side, the top corners,
+    /// then the last six edges with M slice and top layer turns.
+    Roux,
+    /// The ZZ speedsolving method: orient the edges while solving the DF and
+    /// DB edges, build the first two layers with L, U and R, then the last
+    /// layer.
+    Zz,
+    /// The Petrus speedsolving method: a 2x2x2 block, expanded to a 2x2x3
+    /// block, orient the edges, finish the first two layers with R and U,
+    /// then the last layer.
+    Petrus,
+}
+
+/// Solves the cube with the given method, keeping solutions short in the half
+/// turn metric; see [`solve_with_metric`].
+///
+/// Returns an error if the method's search gives up. Panics if the cube can't
+/// be solved; see [`Cube::validate`].
+pub fn solve_with(cube: Cube, method: Method) -> Result<Vec<Move>, SolveError> {
+    solve_with_metric(cube, method, metric::Metric::Htm)
+}
+
+/// Solves the cube with the given method, keeping solutions short in
+/// `metric`.
+///
+/// The two-phase and optimal searches look for short solutions in `metric`,
+/// making slice turns where it counts them as one move. The other methods
+/// follow their own steps, with each search among them counting its moves in
+/// `metric`. The layer by layer method follows fixed algorithms, so gives the
+/// same solution in every metric.
+///
+/// Returns an error if the method's search gives up. Panics if the cube can't
+/// be solved; see [`Cube::validate`].
+pub fn solve_with_metric(
+    cube: Cube,
+    method: Method,
+    metric: metric::Metric,
+) -> Result<Vec<Move>, SolveError> {
+    let stages = match method {
+        Method::LayerByLayer => return Ok(solve(cube)),
+        Method::TwoPhase => return Ok(kociemba::solve_in(&cube, metric)),
+        Method::Optimal => return Ok(optimal::solve_in(&cube, metric)),
+        Method::Thistlethwaite => thistlethwaite::solve_in(&cube, metric),
+        Method::Cfop => cfop::solve_in(&cube, metric),
+        Method::Roux => roux::solve_in(&cube, metric),
+        Method::Zz => zz::solve_in(&cube, metric),
+        Method::Petrus => petrus::solve_in(&cube, metric)?,
+    };
+    Ok(stages.into_iter().flat_map(|stage| stage.moves).collect())
+}
+
+/// Solves the cube layer by layer.
+///
+/// The cube is solved in the [`ColourScheme::WESTERN`] or
+/// [`ColourScheme::JAPANESE`] scheme if its centres match either, and otherwise
+/// with its centres where they are.
+///
+/// Panics if the cube can't be solved; see [`Cube::validate`].
 pub fn solve(cube: Cube) -> Vec<Move> {
+    let scheme = [ColourScheme::WESTERN, ColourScheme::JAPANESE]
+        .into_iter()
+        .find(|&scheme| rotations_to(&cube, scheme).is_some())
+        .unwrap_or_else(|| ColourScheme::of(&cube));
+    solve_with_scheme(cube, scheme)
+}
+
+/// Solves the cube layer by layer, ending with it held as in `scheme`. The
+/// moves are simplified; see [`algorithm::Algorithm::simplify`].
+///
+/// Panics if the cube can't be solved, or if its centres can't be rotated to
+/// match `scheme`.
+pub fn solve_with_scheme(cube: Cube, scheme: ColourScheme) -> Vec<Move> {
+    if let Err(err) = cube.validate() {
+        panic!("cube can't be solved: {}", err);
+    }
     let mut solver = Solver {
         cube,
+        scheme,
         move_stack: Vec::new(),
     };
-    println!("{}", solver.cube);
+    solver.normalise_orientation();
     solver.solve_bottom_cross();
-    println!("{}", solver.cube);
-
-    solver.move_stack
+    solver.solve_bottom_corners();
+    solver.solve_middle_edges();
+    solver.solve_top_cross();
+    solver.orient_top_corners();
+    solver.permute_top_corners();
+    solver.permute_top_edges();
+
+    // The stages are worked out separately, so their moves often cancel where
+    // they meet.
+    algorithm::Algorithm::from(solver.move_stack)
+        .simplify()
+        .into()
 }
@@ -0,0 +1,95 @@
+//! Move counts in the usual turn metrics.
+//!
+//! The metrics differ in which moves they count and how. A slice turn moves
+//! the two outer layers against the centres, so the face turn metrics count it
+//! as two face turns, and a wide turn is a single face turn with the cube
+//! rotated. Rotations don't change the cube, so only the execution turn metric
+//! counts them.
+//!
+//! A slice turn is one move in the slice turn and execution turn metrics, so
+//! the shortest solutions there may need them, and the two-phase and optimal
+//! searches make them in those metrics. The other methods keep to the moves
+//! of their own steps, counting them in the metric; see
+//! [`crate::solve_with_metric`].
+
+use crate::Move;
+
+/// A way of counting moves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Metric {
+    /// The half turn metric: any turn of a face is one move, whatever the
+    /// amount. A slice turn is two.
+    #[default]
+    Htm,
+    /// The quarter turn metric: a quarter turn of a face is one move, and a
+    /// half turn is two. Slice turns count double.
+    Qtm,
+    /// The slice turn metric: any turn of any layer is one move, whatever the
+    /// amount.
+    Stm,
+    /// The execution turn metric: every move is one, rotations included.
+    Etm,
+}
+
+impl Metric {
+    /// All the metrics.
+    pub const ALL: [Metric; 4] = [Metric::Htm, Metric::Qtm, Metric::Stm, Metric::Etm];
+
+    /// The short name of the metric, e.g. `HTM`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Htm => "HTM",
+            Metric::Qtm => "QTM",
+            Metric::Stm => "STM",
+            Metric::Etm => "ETM",
+        }
+    }
+
+    /// How many moves `m` counts as.
+    pub fn cost(self, m: Move) -> usize {
+        use Move::*;
+        // How many face turns `m` makes when counting half turns as one, and
+        // whether it is a half turn.
+        let (turns, half) = match m {
+            F | B | U | D | L | R | FP | BP | UP | DP | LP | RP => (1, false),
+            F2 | B2 | U2 | D2 | L2 | R2 => (1, true),
+            M | E | S | MP | EP | SP => (2, false),
+            M2 | E2 | S2 => (2, true),
+            Fw | Bw | Uw | Dw | Lw | Rw | FwP | BwP | UwP | DwP | LwP | RwP => (1, false),
+            Fw2 | Bw2 | Uw2 | Dw2 | Lw2 | Rw2 => (1, true),
+            X | Y | Z | XP | YP | ZP | X2 | Y2 | Z2 => (0, false),
+        };
+        match self {
+            Metric::Htm => turns,
+            Metric::Qtm if half => 2 * turns,
+            Metric::Qtm => turns,
+            Metric::Stm => turns.min(1),
+            Metric::Etm => 1,
+        }
+    }
+
+    /// How many moves `moves` counts as.
+    pub fn count(self, moves: &[Move]) -> usize {
+        moves.iter().map(|&m| self.cost(m)).sum()
+    }
+}
+
+/// The number of moves in the half turn metric; see [`Metric::Htm`].
+pub fn htm(moves: &[Move]) -> usize {
+    Metric::Htm.count(moves)
+}
+
+/// The number of moves in the quarter turn metric; see [`Metric::Qtm`].
+pub fn qtm(moves: &[Move]) -> usize {
+    Metric::Qtm.count(moves)
+}
+
+/// The number of moves in the slice turn metric; see [`Metric::Stm`].
+pub fn stm(moves: &[Move]) -> usize {
+    Metric::Stm.count(moves)
+}
+
+/// The number of moves in the execution turn metric; see [`Metric::Etm`].
+pub fn etm(moves: &[Move]) -> usize {
+    Metric::Etm.count(moves)
+}
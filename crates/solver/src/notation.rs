@@ -0,0 +1,328 @@
+//! Reading and writing moves in standard notation, e.g. `R U R' U' F2`.
+//!
+//! Each move is a face letter, `M`, `E` or `S` for a slice turn, a lowercase
+//! face letter for a wide turn (`Rw` is also accepted for `r`), or `x`, `y` or
+//! `z` for a rotation of the whole cube. It is optionally followed by `2` for a
+//! half turn or an apostrophe for an anti-clockwise turn. Moves may be
+//! separated by any amount of whitespace, or none at all. Besides `'`, the
+//! typographic apostrophes `’` and `‘`, the prime `′` and the backtick are
+//! accepted.
+//!
+//! Moves can also be written as they are on algorithm sheets, and are expanded
+//! into the moves they stand for:
+//!
+//! - `[A, B]` is the commutator `A B A' B'`, and `[A: B]` the conjugate
+//!   `A B A'`, where `A` and `B` are any sequences, themselves possibly
+//!   commutators or conjugates.
+//! - `(A)` groups a sequence, and `(A)3` repeats it three times.
+//! - Any group, commutator or conjugate can be followed by a count, an
+//!   apostrophe to undo it, or both, e.g. `[R, U]'` or `(R U R' U')2'`. It may
+//!   expand to at most [`MAX_MOVES`] moves.
+//! - Groups, commutators and conjugates may be nested at most [`MAX_DEPTH`]
+//!   deep.
+//! - `//` starts a comment, which runs to the end of the line.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::Move;
+
+/// The most moves a group, commutator or conjugate may expand to, counting
+/// its repeats.
+pub const MAX_MOVES: usize = 100_000;
+/// The most groups, commutators and conjugates that may be nested inside each
+/// other.
+pub const MAX_DEPTH: usize = 64;
+
+/// A problem with move notation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseMoveError {
+    /// The byte offset of the bad token in the input.
+    pub position: usize,
+    /// The bad token, up to the next whitespace or bracket, or empty at the
+    /// end of the input.
+    pub token: String,
+    /// What should have been there instead, if it was a bracket or separator
+    /// rather than a move.
+    pub expected: Option<char>,
+}
+
+impl Display for ParseMoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.expected {
+            None => write!(
+                f,
+                "unknown move `{}` at position {}",
+                self.token, self.position
+            ),
+            Some(expected) if self.token.is_empty() => write!(
+                f,
+                "expected `{}` at position {}, found the end",
+                expected, self.position
+            ),
+            Some(expected) => write!(
+                f,
+                "expected `{}` at position {}, found `{}`",
+                expected, self.position, self.token
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+fn is_apostrophe(c: char) -> bool {
+    matches!(c, '\'' | '’' | '‘' | '′' | '`')
+}
+
+/// The name of each kind of turn, with its clockwise, anti-clockwise and half
+/// turn.
+#[rustfmt::skip]
+pub(crate) const TURNS: [(char, [Move; 3]); 18] = [
+    ('F', [Move::F, Move::FP, Move::F2]),
+    ('B', [Move::B, Move::BP, Move::B2]),
+    ('U', [Move::U, Move::UP, Move::U2]),
+    ('D', [Move::D, Move::DP, Move::D2]),
+    ('L', [Move::L, Move::LP, Move::L2]),
+    ('R', [Move::R, Move::RP, Move::R2]),
+    ('M', [Move::M, Move::MP, Move::M2]),
+    ('E', [Move::E, Move::EP, Move::E2]),
+    ('S', [Move::S, Move::SP, Move::S2]),
+    ('f', [Move::Fw, Move::FwP, Move::Fw2]),
+    ('b', [Move::Bw, Move::BwP, Move::Bw2]),
+    ('u', [Move::Uw, Move::UwP, Move::Uw2]),
+    ('d', [Move::Dw, Move::DwP, Move::Dw2]),
+    ('l', [Move::Lw, Move::LwP, Move::Lw2]),
+    ('r', [Move::Rw, Move::RwP, Move::Rw2]),
+    ('x', [Move::X, Move::XP, Move::X2]),
+    ('y', [Move::Y, Move::YP, Move::Y2]),
+    ('z', [Move::Z, Move::ZP, Move::Z2]),
+];
+
+fn turns(name: char) -> Option<[Move; 3]> {
+    TURNS
+        .iter()
+        .find(|&&(n, _)| n == name)
+        .map(|&(_, turns)| turns)
+}
+
+/// Reads the move at the start of `s`, returning it and the number of bytes
+/// read.
+fn parse_one(s: &str) -> Option<(Move, usize)> {
+    let mut chars = s.char_indices().peekable();
+    let mut name = chars.next()?.1;
+    if name.is_ascii_uppercase() && chars.next_if(|&(_, c)| c == 'w').is_some() {
+        name = name.to_ascii_lowercase();
+    }
+    let [clockwise, anti_clockwise, half] = turns(name)?;
+    let mut result = clockwise;
+    if chars.next_if(|&(_, c)| c == '2').is_some() {
+        // A half turn is the same either way round, so R2' is allowed.
+        chars.next_if(|&(_, c)| is_apostrophe(c));
+        result = half;
+    } else if chars.next_if(|&(_, c)| is_apostrophe(c)).is_some() {
+        result = anti_clockwise;
+    }
+    // A digit or letter straight after the move means it isn't really over,
+    // like R3 or Rq.
+    match chars.peek() {
+        Some(&(_, c)) if c.is_alphanumeric() && turns(c).is_none() => None,
+        Some(&(len, _)) => Some((result, len)),
+        None => Some((result, s.len())),
+    }
+}
+
+/// The characters that group and separate moves rather than being part of
+/// one.
+const PUNCTUATION: [char; 6] = ['[', ']', '(', ')', ',', ':'];
+
+/// Reads notation from left to right.
+struct Parser<'a> {
+    s: &'a str,
+    position: usize,
+    /// The number of brackets opened and not yet closed.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// Skips whitespace and comments, returning what follows.
+    fn rest(&mut self) -> &str {
+        loop {
+            let rest = &self.s[self.position..];
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            if !trimmed.starts_with("//") {
+                return &self.s[self.position..];
+            }
+            self.position += trimmed.find('\n').unwrap_or(trimmed.len());
+        }
+    }
+
+    fn error(&mut self, expected: Option<char>) -> ParseMoveError {
+        let rest = self.rest();
+        let token = match rest.chars().next() {
+            Some(c) if PUNCTUATION.contains(&c) => c.to_string(),
+            _ => rest
+                .split(|c: char| c.is_whitespace() || PUNCTUATION.contains(&c))
+                .next()
+                .unwrap()
+                .to_string(),
+        };
+        ParseMoveError {
+            position: self.position,
+            token,
+            expected,
+        }
+    }
+
+    /// Reads `c`, which must come next.
+    fn expect(&mut self, c: char) -> Result<(), ParseMoveError> {
+        if self.rest().starts_with(c) {
+            self.position += c.len_utf8();
+            Ok(())
+        } else {
+            Err(self.error(Some(c)))
+        }
+    }
+
+    /// Reads the opening bracket that comes next, which is the bad token if
+    /// it nests groups more than [`MAX_DEPTH`] deep.
+    fn open(&mut self) -> Result<(), ParseMoveError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(None));
+        }
+        self.depth += 1;
+        self.position += 1;
+        Ok(())
+    }
+
+    /// Reads the closing bracket `c`, which must come next.
+    fn close(&mut self, c: char) -> Result<(), ParseMoveError> {
+        self.expect(c)?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Reads moves up to the end of the input or a closing bracket or
+    /// separator, which is left to be read.
+    fn sequence(&mut self) -> Result<Vec<Move>, ParseMoveError> {
+        let mut moves = Vec::new();
+        loop {
+            let rest = self.rest();
+            match rest.chars().next() {
+                None | Some(']' | ')' | ',' | ':') => return Ok(moves),
+                Some('[') => {
+                    self.open()?;
+                    let a = self.sequence()?;
+                    let commutator = self.rest().starts_with(',');
+                    if !commutator && !self.rest().starts_with(':') {
+                        return Err(self.error(Some(',')));
+                    }
+                    self.position += 1;
+                    let b = self.sequence()?;
+                    self.close(']')?;
+                    let mut group = [&a[..], &b, &invert(&a)].concat();
+                    if commutator {
+                        group.extend(invert(&b));
+                    }
+                    moves.extend(self.suffix(group)?);
+                }
+                Some('(') => {
+                    self.open()?;
+                    let group = self.sequence()?;
+                    self.close(')')?;
+                    moves.extend(self.suffix(group)?);
+                }
+                Some(_) => {
+                    let (parsed, len) = parse_one(rest).ok_or_else(|| self.error(None))?;
+                    moves.push(parsed);
+                    self.position += len;
+                }
+            }
+        }
+    }
+
+    /// Reads the count and apostrophe that may follow a closing bracket, and
+    /// applies them to the group before it. The count is the bad token if the
+    /// group would be longer than [`MAX_MOVES`].
+    fn suffix(&mut self, group: Vec<Move>) -> Result<Vec<Move>, ParseMoveError> {
+        let rest = &self.s[self.position..];
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let count = match digits {
+            0 => 1,
+            _ => rest[..digits].parse().map_err(|_| self.error(None))?,
+        };
+        match group.len().checked_mul(count) {
+            Some(len) if len <= MAX_MOVES => {}
+            _ => return Err(self.error(None)),
+        }
+        self.position += digits;
+        let mut group = group.repeat(count);
+        if let Some(c) = self.s[self.position..]
+            .chars()
+            .next()
+            .filter(|&c| is_apostrophe(c))
+        {
+            self.position += c.len_utf8();
+            group = invert(&group);
+        }
+        Ok(group)
+    }
+}
+
+fn invert(moves: &[Move]) -> Vec<Move> {
+    moves.iter().rev().map(Move::reverse).collect()
+}
+
+/// Parses a sequence of moves, expanding any commutators, conjugates and
+/// repeated groups.
+pub fn parse(s: &str) -> Result<Vec<Move>, ParseMoveError> {
+    let mut parser = Parser {
+        s,
+        position: 0,
+        depth: 0,
+    };
+    let moves = parser.sequence()?;
+    if parser.rest().is_empty() {
+        Ok(moves)
+    } else {
+        // A closing bracket or separator with nothing to close or separate.
+        Err(parser.error(None))
+    }
+}
+
+/// Writes a sequence of moves in the notation [`parse`] reads, separated by
+/// spaces.
+pub fn format(moves: &[Move]) -> String {
+    moves
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (name, turn) = TURNS
+            .iter()
+            .find_map(|(name, turns)| Some((name, turns.iter().position(|m| m == self)?)))
+            .unwrap();
+        write!(f, "{}{}", name, ["", "'", "2"][turn])
+    }
+}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    /// Parses a single move.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse(s)?[..] {
+            [m] => Ok(m),
+            _ => Err(ParseMoveError {
+                position: s.len() - s.trim_start().len(),
+                token: s.trim().to_string(),
+                expected: None,
+            }),
+        }
+    }
+}
@@ -0,0 +1,390 @@
+//! Optimal solving, in the style of Korf.
+//!
+//! An iterative deepening A* search over face turns, guided by three pattern
+//! databases: one of all eight corners, and one each of two sets of six edges.
+//! Each holds the exact number of moves needed to solve its pieces on their
+//! own, which is a lower bound on the moves needed to solve the whole cube, so
+//! the first solution found is one of the shortest, counting half turns as one
+//! move. Counting them as two only makes the bound looser, so the same search
+//! finds the shortest solutions in the quarter turn metric too. In the slice
+//! turn and execution turn metrics the search makes slice turns as well, each
+//! of which counts as one move but makes two face turns, so the bound is
+//! halved; these searches take much longer.
+//!
+//! The databases have 88 million and 43 million entries, four bits each, and
+//! take some seconds to build the first time they are needed, or minutes in an
+//! unoptimised build. Even with them, a cube twenty moves from solved can take
+//! hours, so [`solve_with_max_nodes`] gives up with [`SolveError::OutOfNodes`]
+//! after visiting a set number of positions.
+
+use std::sync::OnceLock;
+
+use crate::cubie::{CubieCube, Edge, N_CORNER_PERM, N_MOVES, N_TWIST};
+use crate::metric::Metric;
+use crate::search::{face_location_move, move_table, Turns, ALL_MOVES};
+use crate::{Cube, Move, SolveError};
+
+/// The edges of each edge database.
+const EDGE_GROUPS: [[Edge; 6]; 2] = [
+    [Edge::Ur, Edge::Uf, Edge::Ul, Edge::Ub, Edge::Dr, Edge::Df],
+    [Edge::Dl, Edge::Db, Edge::Fr, Edge::Fl, Edge::Bl, Edge::Br],
+];
+
+/// The number of ways to place six distinct edges, 12 * 11 * ... * 7.
+pub(crate) const N_PLACEMENT: usize = 665_280;
+
+/// The number of ways to flip six edges.
+const N_GROUP_FLIP: usize = 64;
+
+/// Marks database entries that haven't been reached yet.
+const UNKNOWN: u8 = 0xf;
+
+/// The positions of six distinct edges, 0..[`N_PLACEMENT`].
+pub(crate) fn placement_index(positions: &[u8; 6]) -> usize {
+    let mut index = 0;
+    for (i, &p) in positions.iter().enumerate() {
+        let earlier = positions[..i].iter().filter(|&&q| q < p).count();
+        index = index * (12 - i) + p as usize - earlier;
+    }
+    index
+}
+
+/// The inverse of [`placement_index`].
+pub(crate) fn placement_from_index(mut index: usize) -> [u8; 6] {
+    let mut digits = [0; 6];
+    for i in (0..6).rev() {
+        digits[i] = index % (12 - i);
+        index /= 12 - i;
+    }
+    let mut available: Vec<u8> = (0..12).collect();
+    digits.map(|digit| available.remove(digit))
+}
+
+/// A table of distances, packed four bits to an entry.
+pub(crate) struct Distances(Vec<u8>);
+
+impl Distances {
+    /// A table of `size` entries, none of them reached yet.
+    pub(crate) fn new(size: usize) -> Distances {
+        Distances(vec![0xff; size.div_ceil(2)])
+    }
+
+    pub(crate) fn get(&self, index: usize) -> u8 {
+        (self.0[index / 2] >> (index % 2 * 4)) & 0xf
+    }
+
+    pub(crate) fn set(&mut self, index: usize, distance: u8) {
+        let shift = index % 2 * 4;
+        let byte = &mut self.0[index / 2];
+        *byte = (*byte & !(0xf << shift)) | (distance << shift);
+    }
+
+    /// Builds the table of the number of face turns needed to bring each of
+    /// `size` coordinates to `solved`, by a breadth first search out from
+    /// there.
+    fn build(size: usize, solved: usize, step: impl Fn(usize, usize) -> usize) -> Distances {
+        let mut table = Distances::new(size);
+        table.set(solved, 0);
+        let mut depth = 0;
+        let mut found = true;
+        while found {
+            found = false;
+            for index in 0..size {
+                if table.get(index) != depth {
+                    continue;
+                }
+                for m in 0..N_MOVES {
+                    let next = step(index, m);
+                    if table.get(next) == UNKNOWN {
+                        table.set(next, depth + 1);
+                        found = true;
+                    }
+                }
+            }
+            depth += 1;
+        }
+        table
+    }
+}
+
+/// How the face turns move a group of six edges, indexed by placement and
+/// flip as `placement * N_GROUP_FLIP + flips`.
+struct EdgeMove {
+    /// Where each face turn takes the edges, by their placement.
+    placement: Vec<[u32; N_MOVES]>,
+    /// Which of the edges each face turn flips, by their placement.
+    flip: Vec<[u8; N_MOVES]>,
+}
+
+impl EdgeMove {
+    fn new() -> EdgeMove {
+        let location_move = &face_location_move()[1];
+        let mut placement = vec![[0; N_MOVES]; N_PLACEMENT];
+        let mut flip = vec![[0; N_MOVES]; N_PLACEMENT];
+        for (index, (placement, flip)) in placement.iter_mut().zip(&mut flip).enumerate() {
+            let positions = placement_from_index(index);
+            for m in 0..N_MOVES {
+                // An unflipped edge at `position` is taken to location
+                // `position * 2 + flip`.
+                let locations = positions.map(|p| location_move[p as usize * 2][m]);
+                placement[m] = placement_index(&locations.map(|l| l / 2)) as u32;
+                flip[m] = locations
+                    .iter()
+                    .enumerate()
+                    .fold(0, |flips, (i, &l)| flips | (l % 2) << i);
+            }
+        }
+        EdgeMove { placement, flip }
+    }
+
+    fn make_face_turn(&self, index: usize, m: usize) -> usize {
+        let (placement, flips) = (index / N_GROUP_FLIP, index % N_GROUP_FLIP);
+        self.placement[placement][m] as usize * N_GROUP_FLIP
+            + (flips ^ self.flip[placement][m] as usize)
+    }
+}
+
+pub(crate) struct Tables {
+    pub(crate) corner_perm_move: Vec<[u16; N_MOVES]>,
+    pub(crate) twist_move: Vec<[u16; N_MOVES]>,
+    edge_move: EdgeMove,
+}
+
+pub(crate) fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| Tables {
+        corner_perm_move: move_table(
+            N_CORNER_PERM,
+            &ALL_MOVES,
+            CubieCube::set_corner_perm,
+            CubieCube::corner_perm,
+        ),
+        twist_move: move_table(N_TWIST, &ALL_MOVES, CubieCube::set_twist, CubieCube::twist),
+        edge_move: EdgeMove::new(),
+    })
+}
+
+struct Databases {
+    /// Moves to solve the corners, by corner permutation and twist.
+    corners: Distances,
+    /// Moves to solve each group of edges.
+    edges: [Distances; 2],
+}
+
+fn databases() -> &'static Databases {
+    static DATABASES: OnceLock<Databases> = OnceLock::new();
+    DATABASES.get_or_init(|| {
+        let t = tables();
+        // The databases are independent, so they are built side by side.
+        let solved = Coords::from_cubie(&CubieCube::SOLVED);
+        let (corners, edges) = std::thread::scope(|scope| {
+            let corners = scope.spawn(|| {
+                Distances::build(N_CORNER_PERM * N_TWIST, 0, |index, m| {
+                    let (corner_perm, twist) = (index / N_TWIST, index % N_TWIST);
+                    t.corner_perm_move[corner_perm][m] as usize * N_TWIST
+                        + t.twist_move[twist][m] as usize
+                })
+            });
+            let edges = solved.edges.map(|solved| {
+                scope.spawn(move || {
+                    Distances::build(N_PLACEMENT * N_GROUP_FLIP, solved, |index, m| {
+                        t.edge_move.make_face_turn(index, m)
+                    })
+                })
+            });
+            (
+                corners.join().unwrap(),
+                edges.map(|edges| edges.join().unwrap()),
+            )
+        });
+        Databases { corners, edges }
+    })
+}
+
+/// The number of face turns needed to solve the pieces of each database on
+/// their own, the most of which is a lower bound for the whole cube.
+fn database_bound(coords: &Coords) -> usize {
+    let d = databases();
+    let corners = d.corners.get(coords.corner_perm * N_TWIST + coords.twist);
+    let edges = d.edges[0]
+        .get(coords.edges[0])
+        .max(d.edges[1].get(coords.edges[1]));
+    corners.max(edges) as usize
+}
+
+/// The coordinates of the cube that index the databases.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Coords {
+    pub(crate) corner_perm: usize,
+    pub(crate) twist: usize,
+    /// The placement and flip of each group of edges.
+    edges: [usize; 2],
+}
+
+impl Coords {
+    pub(crate) fn from_cubie(cube: &CubieCube) -> Coords {
+        let edges = EDGE_GROUPS.map(|group| {
+            let positions = group.map(|edge| cube.ep.iter().position(|&e| e == edge).unwrap());
+            let flips = positions
+                .iter()
+                .enumerate()
+                .fold(0, |flips, (i, &p)| flips | (cube.eo[p] as usize) << i);
+            placement_index(&positions.map(|p| p as u8)) * N_GROUP_FLIP + flips
+        });
+        Coords {
+            corner_perm: cube.corner_perm(),
+            twist: cube.twist(),
+            edges,
+        }
+    }
+
+    pub(crate) fn make_face_turns(&self, t: &Tables, face_turns: &[usize]) -> Coords {
+        face_turns.iter().fold(*self, |coords, &m| Coords {
+            corner_perm: t.corner_perm_move[coords.corner_perm][m] as usize,
+            twist: t.twist_move[coords.twist][m] as usize,
+            edges: coords
+                .edges
+                .map(|index| t.edge_move.make_face_turn(index, m)),
+        })
+    }
+}
+
+struct Search<'a, B> {
+    tables: &'a Tables,
+    turns: &'a Turns,
+    /// A lower bound on the cost of the moves needed to solve the cube.
+    bound: B,
+    solved: Coords,
+    /// How many more positions may be visited.
+    nodes: u64,
+    path: Vec<usize>,
+}
+
+impl<B: Fn(&Coords) -> usize> Search<'_, B> {
+    /// Searches for solutions of exactly `depth` more moves.
+    fn search(&mut self, coords: Coords, depth: usize) -> bool {
+        if self.nodes == 0 {
+            return false;
+        }
+        self.nodes -= 1;
+        if coords == self.solved && self.turns.restore_cost(&self.path) == depth {
+            return true;
+        }
+        if depth == 0 || (self.bound)(&coords) > depth {
+            return false;
+        }
+
+        for m in 0..self.turns.len() {
+            if let Some(&prev) = self.path.last() {
+                if Turns::redundant(prev, m) {
+                    continue;
+                }
+            }
+            let cost = self.turns.cost(m);
+            if cost > depth {
+                continue;
+            }
+            self.path.push(m);
+            let next = coords.make_face_turns(self.tables, self.turns.face_turns(m));
+            if self.search(next, depth - cost) {
+                return true;
+            }
+            self.path.pop();
+        }
+        false
+    }
+}
+
+/// Finds a shortest solution with `turns`, if there is one of at most
+/// `max_length` moves, visiting at most `max_nodes` positions. `bound` must
+/// never overestimate the cost of the moves needed.
+pub(crate) fn search(
+    cube: &Cube,
+    max_length: usize,
+    turns: &Turns,
+    max_nodes: u64,
+    bound: impl Fn(&Coords) -> usize,
+) -> Result<Option<Vec<Move>>, SolveError> {
+    let cube = CubieCube::from_solvable_cube(cube);
+
+    let mut search = Search {
+        tables: tables(),
+        turns,
+        bound,
+        solved: Coords::from_cubie(&CubieCube::SOLVED),
+        nodes: max_nodes,
+        path: Vec::new(),
+    };
+    let coords = Coords::from_cubie(&cube);
+    let found = ((search.bound)(&coords)..=max_length).any(|depth| search.search(coords, depth));
+    if found {
+        Ok(Some(turns.to_moves(&search.path)))
+    } else if search.nodes == 0 {
+        Err(SolveError::OutOfNodes { nodes: max_nodes })
+    } else {
+        Ok(None)
+    }
+}
+
+/// Searches with `metric`'s moves, bounded by the pattern databases.
+fn search_in(
+    cube: &Cube,
+    max_length: usize,
+    metric: Metric,
+    max_nodes: u64,
+) -> Result<Option<Vec<Move>>, SolveError> {
+    let turns = Turns::new(metric);
+    let bound = |coords: &Coords| turns.bound(database_bound(coords));
+    search(cube, max_length, &turns, max_nodes, bound)
+}
+
+/// Finds a shortest solution, if there is one of at most `max_length` face
+/// turns (counting half turns as one).
+///
+/// Panics if the cube can't be solved.
+pub fn solve_with_max_length(cube: &Cube, max_length: usize) -> Option<Vec<Move>> {
+    solve_with_max_length_in(cube, max_length, Metric::Htm)
+}
+
+/// Finds a shortest solution in `metric`, if there is one of at most
+/// `max_length` moves in that metric.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_with_max_length_in(
+    cube: &Cube,
+    max_length: usize,
+    metric: Metric,
+) -> Option<Vec<Move>> {
+    search_in(cube, max_length, metric, u64::MAX).unwrap()
+}
+
+/// Finds a shortest solution, counting half turns as one move.
+///
+/// Panics if the cube can't be solved.
+pub fn solve(cube: &Cube) -> Vec<Move> {
+    solve_in(cube, Metric::Htm)
+}
+
+/// Finds a shortest solution in `metric`.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_in(cube: &Cube, metric: Metric) -> Vec<Move> {
+    solve_with_max_nodes(cube, metric, u64::MAX).unwrap()
+}
+
+/// Finds a shortest solution in `metric`, like [`solve_in`], but gives up
+/// with [`SolveError::OutOfNodes`] after visiting `max_nodes` positions.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_with_max_nodes(
+    cube: &Cube,
+    metric: Metric,
+    max_nodes: u64,
+) -> Result<Vec<Move>, SolveError> {
+    // Every cube can be solved in 20 moves, or 26 quarter turns.
+    let max_length = match metric {
+        Metric::Qtm => 26,
+        _ => 20,
+    };
+    Ok(search_in(cube, max_length, metric, max_nodes)?.unwrap())
+}
@@ -0,0 +1,293 @@
+//! The Petrus method: a 2x2x2 block, expanded to a 2x2x3 block, then orienting
+//! the edges, finishing the first two layers with only R and U, and the last
+//! layer.
+//!
+//! The blocks sit in the bottom left, the 2x2x2 at the back and the 2x2x3
+//! reaching to the front. With the edges oriented relative to the front and
+//! back faces, the rest of the first two layers can be solved with R and U
+//! alone, without disturbing the block.
+//!
+//! Each stage is a search of at most a set number of moves, ending when the
+//! stage's goal holds, e.g. [`block_2x2x3_solved`]. Pattern databases of the
+//! pieces involved keep the search short. A stage that needs more moves than
+//! its limit fails with [`SolveError::StageTooLong`]. [`solve_in`] counts the
+//! moves of each stage in a metric, with the limits scaled to match.
+
+use std::sync::OnceLock;
+
+use crate::cfop::{solve_oll, solve_pll};
+use crate::cubie::{face_turn_move, redundant_pair, Corner, CubieCube, Edge, N_FLIP, N_MOVES};
+use crate::metric::Metric;
+use crate::search::{
+    database, distance_table, face_location_move, face_turn_costs, face_turn_location_move,
+    face_turn_metric, move_table, PerMetric, State, ALL_MOVES,
+};
+use crate::{Cube, SolveError, Stage};
+
+/// The face turns of R and U.
+const RU_MOVES: [usize; 6] = [0, 1, 2, 3, 4, 5];
+
+/// The pieces of the 2x2x2 block, and of the 2x2x2 block at the front of the
+/// 2x2x3 block, as indices into the corners followed by the edges of the
+/// pattern databases.
+const BACK_BLOCK: [usize; 4] = [6, 14, 15, 18];
+const FRONT_BLOCK: [usize; 4] = [5, 13, 14, 17];
+
+/// The pieces of the rest of the first two layers, split into two groups that
+/// each have a database.
+const RIGHT_BLOCK: [[usize; 4]; 2] = [[4, 7, 12, 16], [4, 7, 12, 19]];
+
+/// The most moves each of the 2x2x2 block, 2x2x3 block, EO and F2L stages may
+/// take in [`solve`], generous enough that the searches don't give up in
+/// practice.
+pub const MAX_LENGTHS: [usize; 4] = [10, 14, 12, 20];
+
+struct Tables {
+    back_block: Vec<u8>,
+    front_block: Vec<u8>,
+    flip_move: Vec<[u16; N_MOVES]>,
+    /// Moves to solve each group of the right block with R and U.
+    right_block: [Vec<u8>; 2],
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let location_move = face_location_move();
+        let ru_location_move = face_turn_location_move(&RU_MOVES);
+        Tables {
+            back_block: database(location_move, &BACK_BLOCK),
+            front_block: database(location_move, &FRONT_BLOCK),
+            flip_move: move_table(N_FLIP, &ALL_MOVES, CubieCube::set_flip, CubieCube::flip),
+            right_block: RIGHT_BLOCK.map(|group| database(&ru_location_move, &group)),
+        }
+    })
+}
+
+/// The cost of the moves to orient the edges in a metric, by flip.
+fn flip_distances(metric: Metric) -> &'static Vec<u8> {
+    static FLIP: PerMetric<Vec<u8>> = PerMetric::new();
+    let metric = face_turn_metric(metric);
+    FLIP.get_or_init(metric, || {
+        let t = tables();
+        distance_table(
+            N_FLIP,
+            &[0],
+            &ALL_MOVES,
+            &face_turn_costs(metric),
+            |flip, m| t.flip_move[flip][m] as usize,
+        )
+    })
+}
+
+/// Whether the given pieces are all in place, relative to the centres.
+fn pieces_solved(cube: &Cube, corners: &[Corner], edges: &[Edge]) -> bool {
+    match CubieCube::from_cube(cube) {
+        Ok(cube) => {
+            corners
+                .iter()
+                .all(|&c| cube.cp[c as usize] == c && cube.co[c as usize] == 0)
+                && edges
+                    .iter()
+                    .all(|&e| cube.ep[e as usize] == e && cube.eo[e as usize] == 0)
+        }
+        Err(_) => false,
+    }
+}
+
+/// Whether the 2x2x2 block at the back of the bottom left is solved.
+pub fn block_2x2x2_solved(cube: &Cube) -> bool {
+    pieces_solved(cube, &[Corner::Dbl], &[Edge::Dl, Edge::Db, Edge::Bl])
+}
+
+/// Whether the 2x2x3 block along the bottom left is solved.
+pub fn block_2x2x3_solved(cube: &Cube) -> bool {
+    block_2x2x2_solved(cube) && pieces_solved(cube, &[Corner::Dlf], &[Edge::Df, Edge::Fl])
+}
+
+/// Whether the 2x2x3 block is solved and every edge is oriented.
+pub fn edges_oriented(cube: &Cube) -> bool {
+    block_2x2x3_solved(cube) && cube.misoriented_edges().is_ok_and(|edges| edges.is_empty())
+}
+
+/// Whether the first two layers are solved and every edge is oriented.
+pub fn f2l_solved(cube: &Cube) -> bool {
+    edges_oriented(cube)
+        && pieces_solved(
+            cube,
+            &[Corner::Dfr, Corner::Drb],
+            &[Edge::Dr, Edge::Fr, Edge::Br],
+        )
+}
+
+/// Searches for a cheapest sequence of `moves` costing at most `max_length`
+/// that makes `goal` hold, where face turn `m` costs `costs[m]`. `bound` must
+/// never overestimate the cost of the moves needed, and must be 0 whenever
+/// `goal` holds.
+fn search(
+    cube: &CubieCube,
+    moves: &[usize],
+    costs: &[usize; N_MOVES],
+    max_length: usize,
+    bound: impl Fn(&State) -> u8,
+    goal: fn(&Cube) -> bool,
+) -> Option<Vec<usize>> {
+    struct Search<'a, B> {
+        cube: &'a CubieCube,
+        moves: &'a [usize],
+        costs: &'a [usize; N_MOVES],
+        bound: B,
+        goal: fn(&Cube) -> bool,
+        path: Vec<usize>,
+    }
+
+    impl<B: Fn(&State) -> u8> Search<'_, B> {
+        fn reached(&self) -> bool {
+            let mut cube = self.cube.clone();
+            for &m in &self.path {
+                cube.make_face_turn(m);
+            }
+            (self.goal)(&cube.to_cube())
+        }
+
+        fn search(&mut self, state: State, depth: usize) -> bool {
+            let bound = (self.bound)(&state) as usize;
+            if bound == 0 && self.reached() {
+                return true;
+            }
+            if depth == 0 || bound > depth {
+                return false;
+            }
+            for &m in self.moves {
+                if self
+                    .path
+                    .last()
+                    .is_some_and(|&prev| redundant_pair(prev, m))
+                {
+                    continue;
+                }
+                let Some(depth) = depth.checked_sub(self.costs[m]) else {
+                    continue;
+                };
+                self.path.push(m);
+                let next = state.make_face_turn(m);
+                if self.search(next, depth) {
+                    return true;
+                }
+                self.path.pop();
+            }
+            false
+        }
+    }
+
+    let mut search = Search {
+        cube,
+        moves,
+        costs,
+        bound,
+        goal,
+        path: Vec::new(),
+    };
+    let state = State::from_cubie(cube);
+    let start = (search.bound)(&state) as usize;
+    let found = (start..=max_length).any(|depth| search.search(state, depth));
+    found.then_some(search.path)
+}
+
+/// Solves the cube with Petrus, giving the moves of each stage separately.
+///
+/// Fails with [`SolveError::StageTooLong`] if a stage needs more moves than
+/// [`MAX_LENGTHS`] allows. Panics if the cube can't be solved.
+pub fn solve(cube: &Cube) -> Result<Vec<Stage>, SolveError> {
+    solve_with_max_lengths(cube, MAX_LENGTHS)
+}
+
+/// Like [`solve`], but with the given limits in place of [`MAX_LENGTHS`].
+pub fn solve_with_max_lengths(
+    cube: &Cube,
+    max_lengths: [usize; 4],
+) -> Result<Vec<Stage>, SolveError> {
+    solve_with_max_lengths_in(cube, max_lengths, Metric::Htm)
+}
+
+/// Like [`solve`], but with each stage as short as it can be in `metric`.
+/// The limits of [`MAX_LENGTHS`] are multiplied by the most a face turn costs
+/// in `metric`, so that they allow as many moves as in the half turn metric.
+pub fn solve_in(cube: &Cube, metric: Metric) -> Result<Vec<Stage>, SolveError> {
+    let most = face_turn_costs(metric).into_iter().max().unwrap();
+    solve_with_max_lengths_in(cube, MAX_LENGTHS.map(|length| length * most), metric)
+}
+
+/// Like [`solve_in`], but with the given limits, in `metric`, in place of
+/// [`MAX_LENGTHS`].
+pub fn solve_with_max_lengths_in(
+    cube: &Cube,
+    max_lengths: [usize; 4],
+    metric: Metric,
+) -> Result<Vec<Stage>, SolveError> {
+    let (t, flip) = (tables(), flip_distances(metric));
+    let costs = face_turn_costs(metric);
+    let mut cube = CubieCube::from_solvable_cube(cube);
+
+    let back = |state: &State| t.back_block[state.group_index(&BACK_BLOCK)];
+    let block = |state: &State| back(state).max(t.front_block[state.group_index(&FRONT_BLOCK)]);
+    let right = |state: &State| {
+        RIGHT_BLOCK
+            .iter()
+            .zip(&t.right_block)
+            .map(|(group, database)| database[state.group_index(group)])
+            .max()
+            .unwrap()
+    };
+
+    let mut run = |name, moves: &[usize], max_length, bound: &dyn Fn(&State) -> u8, goal| {
+        let path = search(&cube, moves, &costs, max_length, bound, goal).ok_or(
+            SolveError::StageTooLong {
+                stage: name,
+                max_length,
+            },
+        )?;
+        for &m in &path {
+            cube.make_face_turn(m);
+        }
+        let moves = path.into_iter().map(face_turn_move).collect();
+        Ok(Stage { name, moves })
+    };
+    let mut stages = vec![
+        run(
+            "2x2x2 block",
+            &ALL_MOVES,
+            max_lengths[0],
+            &back,
+            block_2x2x2_solved,
+        )?,
+        run(
+            "2x2x3 block",
+            &ALL_MOVES,
+            max_lengths[1],
+            &block,
+            block_2x2x3_solved,
+        )?,
+        run(
+            "EO",
+            &ALL_MOVES,
+            max_lengths[2],
+            &|state: &State| block(state).max(flip[state.flip()]),
+            edges_oriented,
+        )?,
+        // R and U leave the block and the edge orientation alone.
+        run("F2L", &RU_MOVES, max_lengths[3], &right, f2l_solved)?,
+    ];
+
+    let oll = solve_oll(&mut cube);
+    let pll = solve_pll(&mut cube);
+    stages.push(Stage {
+        name: "OLL",
+        moves: oll,
+    });
+    stages.push(Stage {
+        name: "PLL",
+        moves: pll,
+    });
+    Ok(stages)
+}
@@ -0,0 +1,397 @@
+//! The Roux method: a 1x2x3 block on the left (the first block), another on
+//! the right (the second block), the top corners, then the last six edges
+//! (L6E) with only M slice and top layer turns.
+//!
+//! M slice turns move the centres, so unlike the other solvers this tracks the
+//! pieces with the cube held still, as if the centres were fixed to the left
+//! and right faces, along with how far the M slice centres have turned.
+//!
+//! - The first block is found by a search over face turns.
+//! - The second block is found by a search over `<U, R, M, r>`, which leaves
+//!   the first block alone.
+//! - The top corners are solved in one look with the 42 CMLL algorithms, each
+//!   case recognised by trying the algorithms from each turn of the top layer
+//!   until one works, as for OLL and PLL in CFOP.
+//! - L6E is split into orienting the edges (EO), placing the UL and UR edges,
+//!   and permuting the M slice (EP). There are few enough last six edge states
+//!   to look up the cheapest moves for each of these.
+//!
+//! Each search counts its moves in the metric being solved in, so that, for
+//! instance, an M slice turn costs two face turns in the half turn metric but
+//! only one in the slice turn metric.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::cubie::{face_turn_move, redundant_pair, Corner, CubieCube, Edge, N_MOVES};
+use crate::metric::Metric;
+use crate::search::{
+    database, descend, face_location_move, face_turn_costs, ida_star, location_move_table,
+    Location, PerMetric, State, N_LOCATIONS,
+};
+use crate::{notation, Cube, Move, Stage};
+
+/// The M slice turn, seen with the centres held still. It flips the four
+/// edges it moves.
+#[rustfmt::skip]
+const M_TURN: CubieCube = CubieCube {
+    cp: [Corner::Urf, Corner::Ufl, Corner::Ulb, Corner::Ubr, Corner::Dfr, Corner::Dlf, Corner::Dbl, Corner::Drb],
+    co: [0; 8],
+    ep: [
+        Edge::Ur, Edge::Ub, Edge::Ul, Edge::Db, Edge::Dr, Edge::Uf,
+        Edge::Dl, Edge::Df, Edge::Fr, Edge::Fl, Edge::Bl, Edge::Br,
+    ],
+    eo: [0, 1, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0],
+};
+
+/// The moves used to build the second block.
+#[rustfmt::skip]
+const SB_MOVES: [Move; 12] = [
+    Move::U, Move::U2, Move::UP,
+    Move::R, Move::R2, Move::RP,
+    Move::M, Move::M2, Move::MP,
+    Move::Rw, Move::Rw2, Move::RwP,
+];
+
+/// The moves used for the last six edges, and the quarter turns each makes of
+/// the M slice centres.
+const L6E_MOVES: [Move; 6] = [Move::U, Move::U2, Move::UP, Move::M, Move::M2, Move::MP];
+const L6E_CENTRE_TURNS: [u8; 6] = [0, 0, 0, 1, 2, 3];
+
+/// The indices of [`L6E_MOVES`], as the searches take them.
+const L6E_INDICES: [usize; 6] = [0, 1, 2, 3, 4, 5];
+
+/// The pieces of each block, as indices into the corners followed by the
+/// edges of the pattern databases, split into two groups that each have a
+/// database.
+const FB_GROUPS: [[usize; 4]; 2] = [[5, 6, 14, 17], [5, 6, 14, 18]];
+const SB_GROUPS: [[usize; 4]; 2] = [[4, 7, 12, 16], [4, 7, 12, 19]];
+
+/// The CMLL algorithms, by case, grouped by how the corners are oriented.
+#[rustfmt::skip]
+pub(crate) const CMLL: [(&str, &str); 42] = [
+    ("O Adjacent", "R U R' F' R U R' U' R' F R2 U' R'"),
+    ("O Diagonal", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    ("H Columns", "R U2 R' U' R U R' U' R U' R'"),
+    ("H Rows", "F R U R' U' R U R' U' R U R' U' F'"),
+    ("H Column", "R U2 R2 F R F' U2 R' F R F'"),
+    ("H Row", "r U' r2 D' r U' r' D r2 U r'"),
+    ("Pi Right Bar", "F R U R' U' R U R' U' F'"),
+    ("Pi Back Slash", "F R' F' R U2 R U' R' U R U2 R'"),
+    ("Pi X Checkerboard", "R' F R U F U' R U R' U' F'"),
+    ("Pi Forward Slash", "R U2 R' U' R U R' U2 R' F R F'"),
+    ("Pi Columns", "r U' r2 D' r U r' D r2 U r'"),
+    ("Pi Left Bar", "R' U' R' F R F' R U' R' U2 R"),
+    ("U Forward Slash", "R2 D R' U2 R D' R' U2 R'"),
+    ("U Back Slash", "R2 D' R U2 R' D R U2 R"),
+    ("U Front Row", "R2 F U' F U F2 R2 U' R' F R"),
+    ("U Rows", "F R2 D R' U R D' R2 U' F'"),
+    ("U X Checkerboard", "r U' r' U r' D' r U' r' D r"),
+    ("U Back Row", "F R U R' U' F'"),
+    ("T Left Bar", "R U R' U' R' F R F'"),
+    ("T Right Bar", "L' U' L U L F' L' F"),
+    ("T Rows", "F R' F R2 U' R' U' R U R' F2"),
+    ("T Front Row", "r' U r U2 R2 F R F' R"),
+    ("T Back Row", "r' D' r U r' D r U' r U r'"),
+    ("T Columns", "r2 D' r U r' D r2 U' r' U' r"),
+    ("S Left Bar", "R U R' U R U2 R'"),
+    ("S X Checkerboard", "L' U2 L U2 L F' L' F"),
+    ("S Forward Slash", "F R' F' R U2 R U2 R'"),
+    ("S Columns", "R U R' U R U' R D R' U' R D' R2"),
+    ("S Right Bar", "R U R' U R' F R F' R U2 R'"),
+    ("S Back Slash", "R U' L' U R' U' L"),
+    ("AS Right Bar", "R' U' R U' R' U2 R"),
+    ("AS Columns", "R2 D R' U R D' R' U R' U' R U' R'"),
+    ("AS Back Slash", "F' L F L' U2 L' U2 L"),
+    ("AS X Checkerboard", "R U2 R' U2 R' F R F'"),
+    ("AS Forward Slash", "L' U R U' L U R'"),
+    ("AS Left Bar", "R' U' R U' R' U R' F R F' U R"),
+    ("L Mirror", "F R U' R' U' R U R' F'"),
+    ("L Inverse", "F R' F' R U R U' R'"),
+    ("L Pure", "R U2 R' U' R U R' U' R U R' U' R U' R'"),
+    ("L Front Commutator", "R U2 R D R' U2 R D' R2"),
+    ("L Diag", "R' U' R U R' F' R U R' U' R' F R2"),
+    ("L Back Commutator", "R' U2 R' D' R U2 R' D R2"),
+];
+
+const STAGE_NAMES: [&str; 6] = ["First block", "Second block", "CMLL", "EO", "UL/UR", "EP"];
+
+/// The last six edges, the corner at URF and the M slice centres: the
+/// locations of the edges at UR, UF, UL, UB, DF and DB, then the corner, as in
+/// the pattern databases, and the number of quarter turns of the centres.
+type L6eState = ([Location; 7], u8);
+
+const L6E_PIECES: [usize; 7] = [8, 9, 10, 11, 13, 15, 0];
+
+/// The pieces of a cube, with the centres held still.
+#[derive(Clone)]
+struct RouxCube {
+    cube: CubieCube,
+    /// Quarter turns of the M slice centres, in the direction of M.
+    centres: u8,
+}
+
+/// The effect of a move on the pieces, with the centres held still, and the
+/// number of quarter turns it makes of the M slice centres.
+///
+/// Panics if the move isn't a face turn or an M or r move.
+fn turn(action: Move) -> (CubieCube, u8) {
+    let (face, centres) = match action {
+        Move::M => (None, 1),
+        Move::M2 => (None, 2),
+        Move::MP => (None, 3),
+        Move::Rw => (Some(Move::R), 3),
+        Move::Rw2 => (Some(Move::R2), 2),
+        Move::RwP => (Some(Move::RP), 1),
+        _ => (Some(action), 0),
+    };
+    let mut result = CubieCube::SOLVED;
+    if let Some(face) = face {
+        assert!(
+            (0..N_MOVES).any(|m| face_turn_move(m) == face),
+            "{:?} isn't used by the Roux solver",
+            action
+        );
+        result.make_move(face);
+    }
+    for _ in 0..centres {
+        result.multiply(&M_TURN);
+    }
+    (result, centres)
+}
+
+impl RouxCube {
+    fn make_move(&mut self, action: Move) {
+        let (turn, centres) = turn(action);
+        self.cube.multiply(&turn);
+        self.centres = (self.centres + centres) % 4;
+    }
+
+    fn make_moves(&mut self, moves: &[Move]) {
+        for &m in moves {
+            self.make_move(m);
+        }
+    }
+
+    fn l6e_state(&self) -> L6eState {
+        let state = State::from_cubie(&self.cube);
+        (L6E_PIECES.map(|piece| state.location(piece)), self.centres)
+    }
+}
+
+struct Tables {
+    sb_location_move: [[[Location; 12]; N_LOCATIONS]; 2],
+    l6e_location_move: [[[Location; 6]; N_LOCATIONS]; 2],
+    fb_databases: [Vec<u8>; 2],
+    sb_databases: [Vec<u8>; 2],
+    cmll: Vec<Vec<Move>>,
+}
+
+/// The cost of the moves in a metric to orient the last six edges, to place
+/// the UL and UR edges as well, and to solve the cube.
+struct L6eDistances {
+    eo: HashMap<L6eState, u8>,
+    ulur: HashMap<L6eState, u8>,
+    ep: HashMap<L6eState, u8>,
+}
+
+fn location_moves<const N: usize>(moves: &[Move; N]) -> [[[Location; N]; N_LOCATIONS]; 2] {
+    let turns = moves.map(|m| turn(m).0);
+    [
+        location_move_table(&turns, true),
+        location_move_table(&turns, false),
+    ]
+}
+
+fn make_l6e_move(
+    location_move: &[[[Location; 6]; N_LOCATIONS]; 2],
+    (locations, centres): L6eState,
+    m: usize,
+) -> L6eState {
+    let mut result = locations;
+    for (location, piece) in result.iter_mut().zip(L6E_PIECES) {
+        let kind = usize::from(piece >= 8);
+        *location = location_move[kind][*location as usize][m];
+    }
+    (result, (centres + L6E_CENTRE_TURNS[m]) % 4)
+}
+
+/// The cost of the cheapest moves from every last six edges state to the
+/// nearest of `goals`, where move `m` costs `costs[m]`, by a breadth first
+/// search out from them using `moves` that keeps the states found at each
+/// cost apart.
+fn l6e_distances(
+    location_move: &[[[Location; 6]; N_LOCATIONS]; 2],
+    goals: impl IntoIterator<Item = L6eState>,
+    moves: &[usize],
+    costs: &[usize; 6],
+) -> HashMap<L6eState, u8> {
+    let mut frontiers = vec![goals.into_iter().collect::<Vec<L6eState>>()];
+    let mut table: HashMap<L6eState, u8> = frontiers[0].iter().map(|&state| (state, 0)).collect();
+    let mut depth = 0;
+    while depth < frontiers.len() {
+        for state in std::mem::take(&mut frontiers[depth]) {
+            // A state can be found at a cost and then again at a lower one.
+            if table[&state] as usize != depth {
+                continue;
+            }
+            for &m in moves {
+                let moved = make_l6e_move(location_move, state, m);
+                let cost = depth + costs[m];
+                if table.get(&moved).is_none_or(|&known| cost < known as usize) {
+                    table.insert(moved, cost as u8);
+                    if frontiers.len() <= cost {
+                        frontiers.resize(cost + 1, Vec::new());
+                    }
+                    frontiers[cost].push(moved);
+                }
+            }
+        }
+        depth += 1;
+    }
+    table
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let sb_location_move = location_moves(&SB_MOVES);
+        Tables {
+            cmll: CMLL
+                .iter()
+                .map(|&(_, alg)| notation::parse(alg).unwrap())
+                .collect(),
+            fb_databases: FB_GROUPS.map(|group| database(face_location_move(), &group)),
+            sb_databases: SB_GROUPS.map(|group| database(&sb_location_move, &group)),
+            sb_location_move,
+            l6e_location_move: location_moves(&L6E_MOVES),
+        }
+    })
+}
+
+fn l6e_tables(metric: Metric) -> &'static L6eDistances {
+    static L6E: PerMetric<L6eDistances> = PerMetric::new();
+    L6E.get_or_init(metric, || {
+        let location_move = &tables().l6e_location_move;
+        let costs = L6E_MOVES.map(|m| metric.cost(m));
+        let solved = RouxCube {
+            cube: CubieCube::SOLVED,
+            centres: 0,
+        }
+        .l6e_state();
+        // Oriented edges can be solved with U and M2 alone.
+        let oriented = l6e_distances(location_move, [solved], &[0, 1, 2, 4], &costs);
+        let placed = oriented.keys().copied().filter(|(locations, _)| {
+            locations[0] == solved.0[0] && locations[2] == solved.0[2] && locations[6] == 0
+        });
+        L6eDistances {
+            ulur: l6e_distances(location_move, placed, &L6E_INDICES, &costs),
+            eo: l6e_distances(location_move, oriented.into_keys(), &L6E_INDICES, &costs),
+            ep: l6e_distances(location_move, [solved], &L6E_INDICES, &costs),
+        }
+    })
+}
+
+/// Tries each algorithm after each turn of the top layer, giving the moves of
+/// the first combination that leaves the cube as `done` wants.
+fn recognise(cube: &RouxCube, algs: &[Vec<Move>], done: impl Fn(&CubieCube) -> bool) -> Vec<Move> {
+    if done(&cube.cube) {
+        return Vec::new();
+    }
+    [&[][..], &[Move::U], &[Move::U2], &[Move::UP]]
+        .iter()
+        .flat_map(|&auf| algs.iter().map(move |alg| [auf, alg].concat()))
+        .find(|moves| {
+            let mut cube = cube.clone();
+            cube.make_moves(moves);
+            done(&cube.cube)
+        })
+        .expect("the blocks are solved")
+}
+
+/// Solves the cube with Roux, giving the moves of each stage separately.
+///
+/// Panics if the cube can't be solved.
+pub fn solve(cube: &Cube) -> Vec<Stage> {
+    solve_in(cube, Metric::Htm)
+}
+
+/// Solves the cube like [`solve`], with the blocks and the last six edges as
+/// short as they can be in `metric`.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_in(cube: &Cube, metric: Metric) -> Vec<Stage> {
+    let (t, l6e) = (tables(), l6e_tables(metric));
+    let mut cube = RouxCube {
+        cube: CubieCube::from_solvable_cube(cube),
+        centres: 0,
+    };
+
+    let block_bound = |databases: &[Vec<u8>; 2], groups: &[[usize; 4]; 2], state: &State| {
+        databases
+            .iter()
+            .zip(groups)
+            .map(|(database, group)| database[state.group_index(group)])
+            .max()
+            .unwrap()
+    };
+
+    let fb: Vec<Move> = ida_star(
+        State::from_cubie(&cube.cube),
+        face_location_move(),
+        &face_turn_costs(metric),
+        redundant_pair,
+        |state| block_bound(&t.fb_databases, &FB_GROUPS, state),
+    )
+    .into_iter()
+    .map(face_turn_move)
+    .collect();
+    cube.make_moves(&fb);
+
+    // U is on its own axis, and R, M and r all turn about the same axis, so
+    // are only searched in that order.
+    let sb: Vec<Move> = ida_star(
+        State::from_cubie(&cube.cube),
+        &t.sb_location_move,
+        &SB_MOVES.map(|m| metric.cost(m)),
+        |prev, m| {
+            let (prev_axis, axis) = (prev / 3, m / 3);
+            prev_axis == axis || (prev_axis > 0 && axis > 0 && axis < prev_axis)
+        },
+        |state| block_bound(&t.sb_databases, &SB_GROUPS, state),
+    )
+    .into_iter()
+    .map(|m| SB_MOVES[m])
+    .collect();
+    cube.make_moves(&sb);
+
+    // The corners only need to be solved relative to each other, since the
+    // top layer is turned in L6E anyway.
+    let cmll = recognise(&cube, &t.cmll, |cube| {
+        cube.co[..4] == [0; 4] && (0..4).any(|k| (0..4).all(|i| cube.cp[i] as usize == (i + k) % 4))
+    });
+    cube.make_moves(&cmll);
+
+    let mut stages = vec![fb, sb, cmll];
+    let l6e_costs = L6E_MOVES.map(|m| metric.cost(m));
+    for distances in [&l6e.eo, &l6e.ulur, &l6e.ep] {
+        let moves: Vec<Move> = descend(
+            cube.l6e_state(),
+            &L6E_INDICES,
+            &l6e_costs,
+            |state, m| make_l6e_move(&t.l6e_location_move, state, m),
+            |state| distances[&state],
+        )
+        .into_iter()
+        .map(|m| L6E_MOVES[m])
+        .collect();
+        cube.make_moves(&moves);
+        stages.push(moves);
+    }
+
+    stages
+        .into_iter()
+        .zip(STAGE_NAMES)
+        .map(|(moves, name)| Stage { name, moves })
+        .collect()
+}
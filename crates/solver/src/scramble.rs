@@ -0,0 +1,162 @@
+//! Scrambles for practice and competition.
+//!
+//! A random-state scramble is made by picking a solvable state of the cube
+//! uniformly at random, solving it with the two-phase solver and giving the
+//! solution backwards, so that every state is as likely as any other however
+//! long the solver's solutions are. States that can be solved in fewer than
+//! [`MIN_LENGTH`] moves, five, are drawn again, since a scramble that short is
+//! no test of anything. Checking for them costs a search of every sequence of
+//! up to four moves, but there are so few such states that drawing again
+//! leaves the scrambles as good as uniform.
+//!
+//! A random-move scramble is just random face turns, which is cheap but far
+//! from uniform unless it is long. It never turns the same face twice in a
+//! row, nor a face, its opposite and then the first face again, like `R L R`,
+//! as both would waste moves. The turns can be restricted to those some
+//! generators make, e.g. `<R, U>`, or `<R2, U>` for half turns of R alone.
+//!
+//! Everything random comes from a [`Rng`] started from a seed, so the same
+//! seed always gives the same scramble.
+
+use crate::cubie::{
+    face_turn_index, face_turn_move, parity, redundant_pair, CubieCube, N_FLIP, N_MOVES, N_TWIST,
+};
+
+use crate::{kociemba, notation, Move};
+
+/// The fewest face turns a random-state scramble may be solved in (counting
+/// half turns as one).
+pub const MIN_LENGTH: usize = 5;
+
+/// A small, fast random number generator (SplitMix64), which is plenty for
+/// scrambles and gives the same numbers on every platform.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, as good as uniform for any `n` that fits in memory.
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    /// Puts the items in a uniformly random order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// A uniformly random solvable state of the cube.
+pub fn random_cube(rng: &mut Rng) -> CubieCube {
+    let mut cube = CubieCube::SOLVED;
+    rng.shuffle(&mut cube.cp);
+    rng.shuffle(&mut cube.ep);
+    // Swapping the same two edges whenever the parities differ pairs up the
+    // edge permutations, so the result is still uniform.
+    if parity(&cube.cp.map(|c| c as u8)) != parity(&cube.ep.map(|e| e as u8)) {
+        cube.ep.swap(10, 11);
+    }
+    cube.set_twist(rng.below(N_TWIST));
+    cube.set_flip(rng.below(N_FLIP));
+    cube
+}
+
+/// Whether the cube can be solved in fewer than [`MIN_LENGTH`] moves.
+pub(crate) fn too_short(cube: &CubieCube) -> bool {
+    // So few moves are quicker to try than to bound with pattern databases.
+    fn within(cube: &CubieCube, moves: usize, prev: Option<usize>) -> bool {
+        *cube == CubieCube::SOLVED
+            || moves > 0
+                && (0..N_MOVES)
+                    .filter(|&m| !prev.is_some_and(|prev| redundant_pair(prev, m)))
+                    .any(|m| {
+                        let mut cube = cube.clone();
+                        cube.make_face_turn(m);
+                        within(&cube, moves - 1, Some(m))
+                    })
+    }
+    within(cube, MIN_LENGTH - 1, None)
+}
+
+/// A random-state scramble, in the notation [`notation::parse`] reads.
+pub fn random_state(seed: u64) -> String {
+    notation::format(&random_state_moves(&mut Rng::new(seed)))
+}
+
+/// The moves of a random-state scramble, drawing from `rng`.
+pub fn random_state_moves(rng: &mut Rng) -> Vec<Move> {
+    let cube = loop {
+        let cube = random_cube(rng);
+        if !too_short(&cube) {
+            break cube;
+        }
+    };
+    let solution = kociemba::solve(&cube.to_cube());
+    solution.iter().rev().map(Move::reverse).collect()
+}
+
+/// The clockwise quarter turns of every face, which as generators allow every
+/// face turn.
+pub const ALL_FACES: [Move; 6] = [Move::U, Move::R, Move::F, Move::D, Move::L, Move::B];
+
+/// A random-move scramble of `length` face turns, each one that `generators`
+/// allow, in the notation [`notation::parse`] reads. A quarter turn generator
+/// allows every turn of its face, and a half turn only itself.
+///
+/// Panics if the generators aren't face turns, or are all turns of one face
+/// or of two opposite faces.
+pub fn random_moves(seed: u64, length: usize, generators: &[Move]) -> String {
+    notation::format(&random_moves_with(&mut Rng::new(seed), length, generators))
+}
+
+/// The moves of a random-move scramble, drawing from `rng`, like
+/// [`random_moves`].
+pub fn random_moves_with(rng: &mut Rng, length: usize, generators: &[Move]) -> Vec<Move> {
+    // Faces are numbered as the face turns are, so opposite faces are three
+    // apart and share an axis. A turn of a face is `face * 3 + amount`.
+    let mut amounts: [Vec<usize>; 6] = Default::default();
+    for &g in generators {
+        let m = face_turn_index(g).expect("generators must be face turns");
+        let generated: &[usize] = if m % 3 == 1 { &[1] } else { &[0, 1, 2] };
+        amounts[m / 3].extend(generated);
+        amounts[m / 3].sort_unstable();
+        amounts[m / 3].dedup();
+    }
+    let faces: Vec<usize> = (0..6).filter(|&face| !amounts[face].is_empty()).collect();
+    assert!(
+        faces.iter().any(|&face| face % 3 != faces[0] % 3),
+        "generators must turn faces on at least two axes"
+    );
+
+    let mut moves: Vec<usize> = Vec::with_capacity(length);
+    for _ in 0..length {
+        let allowed: Vec<usize> = faces
+            .iter()
+            .copied()
+            .filter(|&face| match moves[..] {
+                [.., prev] if prev / 3 == face => false,
+                [.., before, prev] => prev / 3 % 3 != face % 3 || before / 3 != face,
+                _ => true,
+            })
+            .collect();
+        let face = allowed[rng.below(allowed.len())];
+        let amounts = &amounts[face];
+        moves.push(face * 3 + amounts[rng.below(amounts.len())]);
+    }
+    moves.into_iter().map(face_turn_move).collect()
+}
@@ -0,0 +1,603 @@
+//! Search machinery shared by the solvers.
+//!
+//! Coordinates, like the corner twist, are updated with move tables, and
+//! pieces with tables of the location each move takes them to. Breadth first
+//! searches over those give exact distance tables to descend, or lower bounds
+//! for an iterative deepening search.
+//!
+//! Distances are counted in a [`Metric`], each move costing what the metric
+//! counts it as. Pattern databases count every move as one, which is never
+//! more than a move costs, so they bound the searches in any metric.
+//!
+//! In the slice turn and execution turn metrics a slice turn costs no more
+//! than a face turn, so the shortest solutions may need them. [`Turns`] adds
+//! them to the face turns for the searches that look for short solutions in
+//! any metric. Wide turns need nothing extra: relative to the centres, `Rw`
+//! is `L` with the cube rotated, and costs the same as `L` in every metric.
+
+use std::sync::OnceLock;
+
+use crate::cubie::{face_turn_move, CubieCube, N_MOVES};
+use crate::metric::Metric;
+use crate::Move;
+
+/// Every face turn.
+pub(crate) const ALL_MOVES: [usize; N_MOVES] =
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17];
+
+/// Marks table entries that haven't been reached yet.
+const UNKNOWN: u8 = u8::MAX;
+
+/// What each face turn counts as in `metric`.
+pub(crate) fn face_turn_costs(metric: Metric) -> [usize; N_MOVES] {
+    std::array::from_fn(|m| metric.cost(face_turn_move(m)))
+}
+
+/// The metric that costs every face turn the same as `metric`, so that
+/// searches over face turns alone can share their tables: the slice turn and
+/// execution turn metrics count face turns like the half turn metric.
+pub(crate) fn face_turn_metric(metric: Metric) -> Metric {
+    match metric {
+        Metric::Qtm => Metric::Qtm,
+        Metric::Htm | Metric::Stm | Metric::Etm => Metric::Htm,
+    }
+}
+
+/// A table for each metric, each built the first time it is needed.
+pub(crate) struct PerMetric<T>([OnceLock<T>; 4]);
+
+impl<T> PerMetric<T> {
+    pub(crate) const fn new() -> PerMetric<T> {
+        PerMetric([const { OnceLock::new() }; 4])
+    }
+
+    pub(crate) fn get_or_init(&self, metric: Metric, init: impl FnOnce() -> T) -> &T {
+        self.0[metric as usize].get_or_init(init)
+    }
+}
+
+/// The faces like which the E, M and S slices turn, D, L and F, indexed by
+/// the axis they turn about: that of U and D, R and L, then F and B.
+const SLICE_FACES: [usize; 3] = [3, 4, 2];
+
+/// The slice turns, indexed by axis, then by amount as for face turns.
+#[rustfmt::skip]
+const SLICE_MOVES: [[Move; 3]; 3] = [
+    [Move::E, Move::E2, Move::EP],
+    [Move::M, Move::M2, Move::MP],
+    [Move::S, Move::S2, Move::SP],
+];
+
+/// Where a quarter turn of the whole cube like U, R and F takes each face.
+const QUARTER_ROTATIONS: [[usize; 6]; 3] =
+    [[0, 2, 4, 3, 5, 1], [5, 1, 0, 2, 4, 3], [1, 3, 2, 4, 0, 5]];
+
+/// The whole cube rotations, and the axis and number of quarter turns of
+/// each.
+#[rustfmt::skip]
+const ROTATIONS: [(Move, usize, usize); 9] = [
+    (Move::Y, 0, 1), (Move::Y2, 0, 2), (Move::YP, 0, 3),
+    (Move::X, 1, 1), (Move::X2, 1, 2), (Move::XP, 1, 3),
+    (Move::Z, 2, 1), (Move::Z2, 2, 2), (Move::ZP, 2, 3),
+];
+
+/// Where the faces of the cube are held, by the face each one started at.
+type Orientation = [usize; 6];
+
+/// Turns the whole cube by `turns` quarter turns like `face`.
+fn rotate(orientation: Orientation, face: usize, turns: usize) -> Orientation {
+    // A quarter turn like D, L or B is three like U, R or F.
+    let turns = if face < 3 { turns } else { 4 - turns };
+    orientation.map(|mut f| {
+        for _ in 0..turns % 4 {
+            f = QUARTER_ROTATIONS[face % 3][f];
+        }
+        f
+    })
+}
+
+/// The moves a search makes to look for short solutions in a metric: every
+/// face turn, then, where the metric counts a slice turn as less than the two
+/// face turns it makes relative to the centres, every slice turn.
+///
+/// Slice turns are numbered from [`N_MOVES`] by axis and amount. They rotate
+/// the centres, so the searches work relative to where the centres started
+/// and [`Turns::to_moves`] turns each move into the one that makes it with
+/// the cube held as it is by then.
+pub(crate) struct Turns {
+    metric: Metric,
+    /// The face turns each move makes, relative to the centres.
+    face_turns: Vec<Vec<usize>>,
+    /// What each move counts as in the metric.
+    costs: Vec<usize>,
+    /// The least any move costs per face turn it makes, doubled.
+    double_cost_per_turn: usize,
+}
+
+impl Turns {
+    pub(crate) fn new(metric: Metric) -> Turns {
+        let mut face_turns: Vec<Vec<usize>> = ALL_MOVES.iter().map(|&m| vec![m]).collect();
+        let mut costs = face_turn_costs(metric).to_vec();
+        // A slice turn is its face turned back and the opposite face turned
+        // on, with the cube rotated like the slice.
+        let slice_turns: Vec<(Vec<usize>, usize)> = SLICE_FACES
+            .iter()
+            .zip(SLICE_MOVES)
+            .flat_map(|(&face, moves)| {
+                (0..3).map(move |amount| {
+                    let turns = vec![face * 3 + 2 - amount, (face + 3) % 6 * 3 + amount];
+                    (turns, metric.cost(moves[amount]))
+                })
+            })
+            .collect();
+        // Every metric counts either all slice turns as cheaper than their
+        // face turns or none of them.
+        if slice_turns
+            .iter()
+            .all(|(turns, cost)| *cost < turns.iter().map(|&m| costs[m]).sum())
+        {
+            for (turns, cost) in slice_turns {
+                face_turns.push(turns);
+                costs.push(cost);
+            }
+        }
+        let double_cost_per_turn = face_turns
+            .iter()
+            .zip(&costs)
+            .map(|(turns, cost)| 2 * cost / turns.len())
+            .min()
+            .unwrap();
+        Turns {
+            metric,
+            face_turns,
+            costs,
+            double_cost_per_turn,
+        }
+    }
+
+    /// The number of moves.
+    pub(crate) fn len(&self) -> usize {
+        self.costs.len()
+    }
+
+    pub(crate) fn face_turns(&self, m: usize) -> &[usize] {
+        &self.face_turns[m]
+    }
+
+    pub(crate) fn cost(&self, m: usize) -> usize {
+        self.costs[m]
+    }
+
+    /// What each move counts as in the metric.
+    pub(crate) fn costs(&self) -> &[usize] {
+        &self.costs
+    }
+
+    /// The axis a move turns about, and its rank among the moves about that
+    /// axis: a turn of U, R or F, then of the opposite face, then the slice.
+    fn axis_rank(m: usize) -> (usize, usize) {
+        if m < N_MOVES {
+            (m / 3 % 3, m / 9)
+        } else {
+            ((m - N_MOVES) / 3, 2)
+        }
+    }
+
+    /// Whether `m` should never be searched after `prev`. Moves about the
+    /// same axis leave the pieces the same in any order, so they are only
+    /// searched in order of rank.
+    pub(crate) fn redundant(prev: usize, m: usize) -> bool {
+        let ((prev_axis, prev_rank), (axis, rank)) = (Turns::axis_rank(prev), Turns::axis_rank(m));
+        axis == prev_axis && rank <= prev_rank
+    }
+
+    /// A lower bound on the cost of the moves needed, from a lower bound on
+    /// the number of face turns needed.
+    pub(crate) fn bound(&self, face_turns: usize) -> usize {
+        (face_turns * self.double_cost_per_turn).div_ceil(2)
+    }
+
+    /// Where the faces are held after `path`, relative to where they started.
+    fn orientation(path: &[usize]) -> Orientation {
+        path.iter()
+            .filter(|&&m| m >= N_MOVES)
+            .fold([0, 1, 2, 3, 4, 5], |orientation, &m| {
+                let (axis, amount) = ((m - N_MOVES) / 3, (m - N_MOVES) % 3);
+                rotate(orientation, orientation[SLICE_FACES[axis]], amount + 1)
+            })
+    }
+
+    /// The fewest rotations that bring the cube back to how it was held at the
+    /// start.
+    fn restoring(orientation: Orientation) -> Vec<Move> {
+        let rotated = |rotations: &[(Move, usize, usize)]| {
+            rotations
+                .iter()
+                .fold(orientation, |orientation, &(_, axis, turns)| {
+                    rotate(orientation, axis, turns)
+                })
+        };
+        let singles = ROTATIONS.iter().map(|&rotation| vec![rotation]);
+        let pairs = ROTATIONS
+            .iter()
+            .flat_map(|&first| ROTATIONS.iter().map(move |&second| vec![first, second]));
+        std::iter::once(Vec::new())
+            .chain(singles)
+            .chain(pairs)
+            .find(|rotations| rotated(rotations) == [0, 1, 2, 3, 4, 5])
+            .expect("every orientation is two rotations from any other")
+            .into_iter()
+            .map(|(rotation, _, _)| rotation)
+            .collect()
+    }
+
+    /// What the rotations that end `path` cost, which is nothing except in
+    /// the execution turn metric.
+    pub(crate) fn restore_cost(&self, path: &[usize]) -> usize {
+        if path.iter().all(|&m| m < N_MOVES) {
+            return 0;
+        }
+        self.metric
+            .count(&Turns::restoring(Turns::orientation(path)))
+    }
+
+    /// The moves that make `path`, ending with the cube held as it started.
+    pub(crate) fn to_moves(&self, path: &[usize]) -> Vec<Move> {
+        let mut orientation = [0, 1, 2, 3, 4, 5];
+        let mut moves = Vec::new();
+        for &m in path {
+            if m < N_MOVES {
+                moves.push(face_turn_move(orientation[m / 3] * 3 + m % 3));
+                continue;
+            }
+            let (axis, amount) = ((m - N_MOVES) / 3, (m - N_MOVES) % 3);
+            // The slice now turns about the axis of the face its own face has
+            // been rotated to, and turns the other way if that face is
+            // opposite the one the slice turns like.
+            let face = orientation[SLICE_FACES[axis]];
+            moves.push(if face == SLICE_FACES[face % 3] {
+                SLICE_MOVES[face % 3][amount]
+            } else {
+                SLICE_MOVES[face % 3][2 - amount]
+            });
+            orientation = rotate(orientation, face, amount + 1);
+        }
+        moves.extend(Turns::restoring(orientation));
+        moves
+    }
+}
+
+/// Builds the table of how each face turn changes a coordinate.
+///
+/// Only the turns in `moves` are filled in; the rest are left as 0.
+pub(crate) fn move_table(
+    size: usize,
+    moves: &[usize],
+    set: impl Fn(&mut CubieCube, usize),
+    get: impl Fn(&CubieCube) -> usize,
+) -> Vec<[u16; N_MOVES]> {
+    let mut table = vec![[0; N_MOVES]; size];
+    let mut cube = CubieCube::SOLVED;
+    for (coord, entry) in table.iter_mut().enumerate() {
+        for &m in moves {
+            set(&mut cube, coord);
+            cube.make_face_turn(m);
+            entry[m] = get(&cube) as u16;
+        }
+    }
+    table
+}
+
+/// Makes the face turns of `face_turns` on a coordinate, given how each face
+/// turn changes it.
+pub(crate) fn move_coord(table: &[[u16; N_MOVES]], coord: usize, face_turns: &[usize]) -> usize {
+    face_turns
+        .iter()
+        .fold(coord, |coord, &m| table[coord][m] as usize)
+}
+
+/// Builds a pruning table holding the cost of the cheapest of `moves` of
+/// `turns` to bring a pair of coordinates to `(0, 0)`, by a breadth first
+/// search out from there.
+///
+/// The entry for `(a, b)` is at `a * b_size + b`.
+pub(crate) fn prune_table(
+    a_move: &[[u16; N_MOVES]],
+    b_move: &[[u16; N_MOVES]],
+    turns: &Turns,
+    moves: &[usize],
+) -> Vec<u8> {
+    let b_size = b_move.len();
+    distance_table(
+        a_move.len() * b_size,
+        &[0],
+        moves,
+        turns.costs(),
+        |index, m| {
+            let face_turns = turns.face_turns(m);
+            move_coord(a_move, index / b_size, face_turns) * b_size
+                + move_coord(b_move, index % b_size, face_turns)
+        },
+    )
+}
+
+/// Builds a table of the cost of the cheapest moves to reach any of `goals`,
+/// where move `m` costs `costs[m]`, by a breadth first search out from them
+/// that keeps the states found at each cost apart.
+pub(crate) fn distance_table(
+    size: usize,
+    goals: &[usize],
+    moves: &[usize],
+    costs: &[usize],
+    step: impl Fn(usize, usize) -> usize,
+) -> Vec<u8> {
+    let mut table = vec![UNKNOWN; size];
+    for &goal in goals {
+        table[goal] = 0;
+    }
+    let mut frontiers = vec![goals.to_vec()];
+    let mut depth = 0;
+    while depth < frontiers.len() {
+        for index in std::mem::take(&mut frontiers[depth]) {
+            // A state can be found at a cost and then again at a lower one.
+            if table[index] as usize != depth {
+                continue;
+            }
+            for &m in moves {
+                let moved = step(index, m);
+                let cost = depth + costs[m];
+                if cost < table[moved] as usize {
+                    table[moved] = cost as u8;
+                    if frontiers.len() <= cost {
+                        frontiers.resize(cost + 1, Vec::new());
+                    }
+                    frontiers[cost].push(moved);
+                }
+            }
+        }
+        depth += 1;
+    }
+    table
+}
+
+/// Makes the face turns in `path`.
+pub(crate) fn make_face_turns(cube: &mut CubieCube, path: &[usize]) {
+    for &m in path {
+        cube.make_face_turn(m);
+    }
+}
+
+/// Where a piece is, as `position * orientations + orientation`. Corners have
+/// 8 positions and 3 orientations, and edges 12 positions and 2 orientations,
+/// so both have 24 locations.
+pub(crate) type Location = u8;
+
+pub(crate) const N_LOCATIONS: usize = 24;
+
+const N_PIECES: usize = 20;
+
+/// The locations of all the pieces, corners first.
+#[derive(Clone, Copy)]
+pub(crate) struct State([Location; N_PIECES]);
+
+impl State {
+    pub(crate) fn from_cubie(cube: &CubieCube) -> State {
+        let mut result = [0; N_PIECES];
+        for (pos, (&piece, &ori)) in cube.cp.iter().zip(&cube.co).enumerate() {
+            result[piece as usize] = (pos * 3) as Location + ori;
+        }
+        for (pos, (&piece, &ori)) in cube.ep.iter().zip(&cube.eo).enumerate() {
+            result[8 + piece as usize] = (pos * 2) as Location + ori;
+        }
+        State(result)
+    }
+
+    pub(crate) fn make_face_turn(&self, m: usize) -> State {
+        self.make_move(face_location_move(), m)
+    }
+
+    /// Makes move `m` of a set of moves, given the location each move takes a
+    /// piece to.
+    pub(crate) fn make_move<const N: usize>(
+        &self,
+        location_move: &[[[Location; N]; N_LOCATIONS]; 2],
+        m: usize,
+    ) -> State {
+        let mut result = self.0;
+        for (piece, location) in result.iter_mut().enumerate() {
+            let kind = usize::from(piece >= 8);
+            *location = location_move[kind][*location as usize][m];
+        }
+        State(result)
+    }
+
+    /// The location of a piece, indexed as corners followed by edges.
+    pub(crate) fn location(&self, piece: usize) -> Location {
+        self.0[piece]
+    }
+
+    /// The orientation of the edges, as [`CubieCube::flip`] gives it.
+    pub(crate) fn flip(&self) -> usize {
+        let mut eo = [0; 12];
+        for &location in &self.0[8..] {
+            eo[location as usize / 2] = location % 2;
+        }
+        eo[..11].iter().fold(0, |acc, &o| acc * 2 + o as usize)
+    }
+
+    pub(crate) fn group_index(&self, group: &[usize]) -> usize {
+        group
+            .iter()
+            .fold(0, |acc, &piece| acc * N_LOCATIONS + self.0[piece] as usize)
+    }
+}
+
+/// The location each of `turns` takes a corner or edge to.
+pub(crate) fn location_move_table<const N: usize>(
+    turns: &[CubieCube; N],
+    corners: bool,
+) -> [[Location; N]; N_LOCATIONS] {
+    let mut table = [[0; N]; N_LOCATIONS];
+    for (m, turn) in turns.iter().enumerate() {
+        // The turn puts the piece from position `cp[to]` at `to`, twisting it
+        // by `co[to]`.
+        if corners {
+            for (to, (&from, &twist)) in turn.cp.iter().zip(&turn.co).enumerate() {
+                for ori in 0..3 {
+                    table[from as usize * 3 + ori][m] =
+                        (to * 3 + (ori + twist as usize) % 3) as Location;
+                }
+            }
+        } else {
+            for (to, (&from, &flip)) in turn.ep.iter().zip(&turn.eo).enumerate() {
+                for ori in 0..2 {
+                    table[from as usize * 2 + ori][m] =
+                        (to * 2 + (ori + flip as usize) % 2) as Location;
+                }
+            }
+        }
+    }
+    table
+}
+
+/// Builds the pattern database for a group of pieces, the number of moves
+/// needed to solve them, by a breadth first search out from the solved cube.
+pub(crate) fn database<const N: usize>(
+    location_move: &[[[Location; N]; N_LOCATIONS]; 2],
+    group: &[usize],
+) -> Vec<u8> {
+    database_in(location_move, group, &[1; N])
+}
+
+/// Builds the pattern database for a group of pieces like [`database`], but
+/// holding the cost of the cheapest moves to solve them, where move `m` costs
+/// `costs[m]`.
+pub(crate) fn database_in<const N: usize>(
+    location_move: &[[[Location; N]; N_LOCATIONS]; 2],
+    group: &[usize],
+    costs: &[usize; N],
+) -> Vec<u8> {
+    let kinds: Vec<_> = group
+        .iter()
+        .map(|&piece| &location_move[usize::from(piece >= 8)])
+        .collect();
+    let solved = State::from_cubie(&CubieCube::SOLVED).group_index(group);
+    let moves: [usize; N] = std::array::from_fn(|m| m);
+    distance_table(
+        N_LOCATIONS.pow(group.len() as u32),
+        &[solved],
+        &moves,
+        costs,
+        |index, m| {
+            kinds.iter().enumerate().fold(0, |acc, (i, kind)| {
+                let shift = N_LOCATIONS.pow((group.len() - 1 - i) as u32);
+                acc * N_LOCATIONS + kind[index / shift % N_LOCATIONS][m] as usize
+            })
+        },
+    )
+}
+
+/// The location each of the face turns in `moves` takes a corner or edge to,
+/// for corners then edges.
+pub(crate) fn face_turn_location_move<const N: usize>(
+    moves: &[usize; N],
+) -> [[[Location; N]; N_LOCATIONS]; 2] {
+    let turns = moves.map(|m| {
+        let mut turn = CubieCube::SOLVED;
+        turn.make_face_turn(m);
+        turn
+    });
+    [
+        location_move_table(&turns, true),
+        location_move_table(&turns, false),
+    ]
+}
+
+/// The location every face turn takes a corner or edge to, for corners then
+/// edges.
+pub(crate) fn face_location_move() -> &'static [[[Location; N_MOVES]; N_LOCATIONS]; 2] {
+    static TABLE: OnceLock<[[[Location; N_MOVES]; N_LOCATIONS]; 2]> = OnceLock::new();
+    TABLE.get_or_init(|| face_turn_location_move(&ALL_MOVES))
+}
+
+/// Finds a cheapest sequence of moves to a state where `bound` is 0, where
+/// move `m` costs `costs[m]`, by an iterative deepening search. `bound` must
+/// never overestimate the cost of the moves needed.
+pub(crate) fn ida_star<const N: usize>(
+    start: State,
+    location_move: &[[[Location; N]; N_LOCATIONS]; 2],
+    costs: &[usize; N],
+    redundant: impl Fn(usize, usize) -> bool,
+    bound: impl Fn(&State) -> u8,
+) -> Vec<usize> {
+    struct Search<'a, const N: usize> {
+        location_move: &'a [[[Location; N]; N_LOCATIONS]; 2],
+        costs: &'a [usize; N],
+        redundant: &'a dyn Fn(usize, usize) -> bool,
+        bound: &'a dyn Fn(&State) -> u8,
+        path: Vec<usize>,
+    }
+
+    impl<const N: usize> Search<'_, N> {
+        /// Searches for moves costing exactly `depth`.
+        fn extend(&mut self, state: State, depth: usize) -> bool {
+            let estimate = (self.bound)(&state) as usize;
+            if depth == 0 || estimate > depth {
+                return depth == 0 && estimate == 0;
+            }
+            for m in 0..N {
+                if self
+                    .path
+                    .last()
+                    .is_some_and(|&prev| (self.redundant)(prev, m))
+                {
+                    continue;
+                }
+                let Some(depth) = depth.checked_sub(self.costs[m]) else {
+                    continue;
+                };
+                self.path.push(m);
+                if self.extend(state.make_move(self.location_move, m), depth) {
+                    return true;
+                }
+                self.path.pop();
+            }
+            false
+        }
+    }
+
+    let mut search = Search {
+        location_move,
+        costs,
+        redundant: &redundant,
+        bound: &bound,
+        path: Vec::new(),
+    };
+    let mut depth = bound(&start) as usize;
+    while !search.extend(start, depth) {
+        depth += 1;
+    }
+    search.path
+}
+
+/// Brings `state` to distance 0 by always making the first move that brings
+/// the distance down by what it costs, where move `m` costs `costs[m]`.
+pub(crate) fn descend<S: Copy>(
+    mut state: S,
+    moves: &[usize],
+    costs: &[usize],
+    step: impl Fn(S, usize) -> S,
+    distance: impl Fn(S) -> u8,
+) -> Vec<usize> {
+    let mut path = Vec::new();
+    while distance(state) > 0 {
+        let (m, next) = moves
+            .iter()
+            .map(|&m| (m, step(state, m)))
+            .find(|&(m, next)| distance(next) as usize + costs[m] == distance(state) as usize)
+            .expect("distance table is exact");
+        path.push(m);
+        state = next;
+    }
+    path
+}
@@ -0,0 +1,80 @@
+use super::*;
+use crate::algorithm::{Algorithm, Plane};
+
+fn alg(s: &str) -> Algorithm {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_inverse() {
+    assert_eq!(alg("R U2 F' x").inverse(), alg("x' F U2 R'"));
+    let sune = alg("R U R' U R U2 R'");
+    let mut cube = scrambled(&sune.moves);
+    for &m in &sune.inverse().moves {
+        cube.make_move(m);
+    }
+    assert!(cube.faces == Cube::SOLVED.faces);
+}
+
+#[test]
+fn test_mirror() {
+    let sune = alg("R U R' U R U2 R'");
+    assert_eq!(sune.mirror(Plane::M), alg("L' U' L U' L' U2 L"));
+    assert_eq!(
+        alg("M E S x y z r u f").mirror(Plane::M),
+        alg("M E' S' x y' z' l' u' f'")
+    );
+    assert_eq!(
+        alg("R U F M E S x y z").mirror(Plane::S),
+        alg("R' U' B' M' E' S x' y' z")
+    );
+    assert_eq!(alg("U R D2").mirror(Plane::E), alg("D' R' U2"));
+    for plane in [Plane::M, Plane::E, Plane::S] {
+        assert_eq!(sune.mirror(plane).mirror(plane), sune);
+    }
+}
+
+#[test]
+fn test_rotate() {
+    assert_eq!(alg("R U R'").rotate(Move::Y), alg("B U B'"));
+    assert_eq!(alg("R U F").rotate(Move::X), alg("R F D"));
+    // Rotating the algorithm is the same as rotating the cube around it.
+    let moves = alg("R U' F2 L D B' M E' S2 r u f' x y2 z'");
+    for rotation in [Move::X, Move::YP, Move::Z2] {
+        let rotated = moves.rotate(rotation);
+        let mut around = vec![rotation];
+        around.extend(&moves.moves);
+        around.push(rotation.reverse());
+        assert!(scrambled(&rotated.moves).faces == scrambled(&around).faces);
+    }
+}
+
+#[test]
+fn test_simplify() {
+    assert_eq!(alg("U U U").simplify(), alg("U'"));
+    assert_eq!(alg("R L R'").simplify(), alg("L"));
+    assert_eq!(alg("R U U' R'").simplify(), alg(""));
+    assert_eq!(alg("R L2 R L2").simplify(), alg("R2"));
+    assert_eq!(alg("U D U' F R M x R'").simplify(), alg("D F M x"));
+    assert_eq!(alg("r R r'").simplify(), alg("R"));
+    assert_eq!(alg("R U R'").simplify(), alg("R U R'"));
+    for cube in scrambles().take(5) {
+        let moves = solve(cube.clone());
+        let simplified = Algorithm::from(moves.clone()).simplify();
+        assert_eq!(simplified.moves, moves);
+    }
+}
+
+#[test]
+fn test_simplify_keeps_effect() {
+    let moves = alg("R L R' M2 x' L2 x U D' U2 E y D F B F' S z' r l' r u U' d");
+    let simplified = moves.simplify();
+    assert!(simplified.moves.len() < moves.moves.len());
+    assert!(scrambled(&simplified.moves).faces == scrambled(&moves.moves).faces);
+}
+
+#[test]
+fn test_format() {
+    assert_eq!(alg("R U2 F'").to_string(), "R U2 F'");
+    assert_eq!(Vec::from(alg("R U")), [Move::R, Move::U]);
+}
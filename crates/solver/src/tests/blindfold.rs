@@ -0,0 +1,214 @@
+use super::*;
+use crate::algorithm::Algorithm;
+use crate::blindfold::{old_pochmann, solve, CornerMethod, EdgeMethod, Lettering};
+use crate::cubie::{Corner, CubieCube, Edge};
+use crate::notation::parse;
+
+#[test]
+fn test_solved() {
+    let solution = old_pochmann(&Cube::SOLVED, &Lettering::SPEFFZ);
+    assert!(solution.memo.edges.is_empty());
+    assert!(solution.memo.corners.is_empty());
+    assert!(solution.steps.is_empty());
+}
+
+#[test]
+fn test_memo() {
+    // The T-perm swaps the edge buffer with its helper, and two corners that
+    // take a new cycle to solve.
+    let cube = scrambled(&parse("R U R' U' R' F R2 U' R' U' R U R' F'").unwrap());
+    let solution = old_pochmann(&cube, &Lettering::default());
+    assert_eq!(solution.memo.edges, ['D']);
+    assert_eq!(solution.memo.corners, ['B', 'C', 'B']);
+    assert!(solution.memo.parity);
+    assert_eq!(solution.memo.edge_pairs(), ["D"]);
+    assert_eq!(solution.memo.corner_pairs(), ["BC", "B"]);
+    assert_solves(cube.clone(), &solution.moves());
+
+    let lettering = Lettering {
+        corners: Lettering::SPEFFZ.corners.map(|c| c.to_ascii_lowercase()),
+        ..Lettering::SPEFFZ
+    };
+    let solution = old_pochmann(&cube, &lettering);
+    assert_eq!(solution.memo.edges, ['D']);
+    assert_eq!(solution.memo.corners, ['b', 'c', 'b']);
+}
+
+#[test]
+fn test_flips_and_twists() {
+    let mut cube = CubieCube::SOLVED;
+    cube.eo[Edge::Uf as usize] = 1;
+    cube.eo[Edge::Fr as usize] = 1;
+    cube.co[Corner::Urf as usize] = 1;
+    cube.co[Corner::Dbl as usize] = 2;
+    let cube = cube.to_cube();
+
+    // Each flipped or twisted piece is shot to twice, once for each of two of
+    // its stickers.
+    let solution = old_pochmann(&cube, &Lettering::SPEFFZ);
+    assert_eq!(solution.memo.edges, ['C', 'I', 'J', 'P']);
+    assert_eq!(solution.memo.corners.len(), 4);
+    assert!(!solution.memo.parity);
+    assert_solves(cube, &solution.moves());
+}
+
+#[test]
+fn test_solve() {
+    for cube in scrambles().take(20) {
+        let solution = old_pochmann(&cube, &Lettering::SPEFFZ);
+        assert_eq!(
+            solution.memo.edges.len() % 2,
+            solution.memo.corners.len() % 2
+        );
+        assert_solves(cube, &solution.moves());
+    }
+}
+
+#[test]
+fn test_steps() {
+    let cube = scrambled(&parse("R U R' U' R' F R2 U' R' U' R U R' F'").unwrap());
+    let solution = old_pochmann(&cube, &Lettering::SPEFFZ);
+    let letters: Vec<&str> = solution.steps.iter().map(|s| s.letters.as_str()).collect();
+    assert_eq!(letters, ["D", "", "BC", "B"]);
+
+    // With any other method, the parity algorithm comes last.
+    let solution = solve(&cube, EdgeMethod::M2, CornerMethod::R2, &Lettering::SPEFFZ);
+    assert!(solution.memo.parity);
+    assert_eq!(solution.steps.last().unwrap().letters, "");
+    assert_eq!(solution.memo.edges.len() % 2, 0);
+    assert_eq!(solution.memo.corners.len() % 2, 0);
+    assert_solves(cube, &solution.moves());
+}
+
+#[test]
+fn test_methods() {
+    let methods = [
+        (EdgeMethod::M2, CornerMethod::R2),
+        (EdgeMethod::ThreeStyle, CornerMethod::ThreeStyle),
+        (EdgeMethod::M2, CornerMethod::OldPochmann),
+        (EdgeMethod::ThreeStyle, CornerMethod::R2),
+    ];
+    for (cube, (edges, corners)) in scrambles().zip(methods.iter().cycle()).take(20) {
+        let solution = solve(&cube, *edges, *corners, &Lettering::SPEFFZ);
+        let pairs = [solution.memo.edge_pairs(), solution.memo.corner_pairs()].concat();
+        let letters: Vec<String> = solution
+            .steps
+            .iter()
+            .map(|s| s.letters.clone())
+            .filter(|letters| !letters.is_empty())
+            .collect();
+        assert_eq!(letters, pairs);
+        assert_solves(cube, &solution.moves());
+    }
+}
+
+#[test]
+fn test_three_style() {
+    // A three-cycle is a single pair, solved by a commutator at least as
+    // short as the one that made it.
+    for (scramble, edges, corners) in [("R U R' D R U' R' D'", 0, 2), ("M' U2 M U2", 2, 0)] {
+        let scramble = parse(scramble).unwrap();
+        let cube = scrambled(&scramble);
+        let solution = solve(
+            &cube,
+            EdgeMethod::ThreeStyle,
+            CornerMethod::ThreeStyle,
+            &Lettering::SPEFFZ,
+        );
+        assert_eq!(solution.memo.edges.len(), edges);
+        assert_eq!(solution.memo.corners.len(), corners);
+        assert_eq!(solution.steps.len(), 1);
+        assert!(solution.steps[0].moves.len() <= scramble.len());
+        assert_solves(cube, &solution.moves());
+    }
+}
+
+/// Whether no two moves in a row turn the same layer, and nothing else would
+/// simplify.
+fn is_simplified(moves: &[Move]) -> bool {
+    let layer = |m: &Move| m.to_string().trim_end_matches(['\'', '2']).to_string();
+    moves
+        .windows(2)
+        .all(|pair| layer(&pair[0]) != layer(&pair[1]))
+        && Algorithm::from(moves.to_vec()).simplify().moves == moves
+}
+
+#[test]
+fn test_every_pair() {
+    // Every three-cycle of the buffer and two other pieces, each way round,
+    // which is memorised as a single pair of letters.
+    let mut cubes = Vec::new();
+    for first in 0..12 {
+        for second in (0..12).filter(|&e| e != first) {
+            if first == Edge::Uf as usize || second == Edge::Uf as usize {
+                continue;
+            }
+            for flip in 0..4 {
+                let mut cube = CubieCube::SOLVED;
+                cube.ep[Edge::Uf as usize] = cube.ep[first];
+                cube.ep[first] = cube.ep[second];
+                cube.ep[second] = Edge::Uf;
+                cube.eo[Edge::Uf as usize] = flip & 1;
+                cube.eo[first] = flip >> 1;
+                cube.eo[second] = (flip & 1) ^ (flip >> 1);
+                cubes.push((cube, 2, 0));
+            }
+        }
+    }
+    for first in 0..8 {
+        for second in (0..8).filter(|&c| c != first) {
+            if first == Corner::Urf as usize || second == Corner::Urf as usize {
+                continue;
+            }
+            for twist in 0..9 {
+                let mut cube = CubieCube::SOLVED;
+                cube.cp[Corner::Urf as usize] = cube.cp[first];
+                cube.cp[first] = cube.cp[second];
+                cube.cp[second] = Corner::Urf;
+                cube.co[Corner::Urf as usize] = twist % 3;
+                cube.co[first] = twist / 3;
+                cube.co[second] = (6 - twist % 3 - twist / 3) % 3;
+                cubes.push((cube, 0, 2));
+            }
+        }
+    }
+    assert_eq!(cubes.len(), 440 + 378);
+
+    for (cube, edges, corners) in cubes {
+        let cube = cube.to_cube();
+        let solution = solve(
+            &cube,
+            EdgeMethod::ThreeStyle,
+            CornerMethod::ThreeStyle,
+            &Lettering::SPEFFZ,
+        );
+        assert_eq!(solution.memo.edges.len(), edges);
+        assert_eq!(solution.memo.corners.len(), corners);
+        assert_eq!(solution.steps.len(), 1);
+        let moves = &solution.steps[0].moves;
+        assert!(
+            is_simplified(moves),
+            "{} isn't simplified",
+            Algorithm::from(moves.clone())
+        );
+        assert_solves(cube, moves);
+    }
+}
+
+#[test]
+fn test_simplified_steps() {
+    let methods = [
+        (EdgeMethod::OldPochmann, CornerMethod::OldPochmann),
+        (EdgeMethod::M2, CornerMethod::R2),
+        (EdgeMethod::ThreeStyle, CornerMethod::ThreeStyle),
+    ];
+    for (cube, (edges, corners)) in scrambles().zip(methods.iter().cycle()).take(12) {
+        let solution = solve(&cube, *edges, *corners, &Lettering::SPEFFZ);
+        for step in &solution.steps {
+            let algorithm = Algorithm::from(step.moves.clone());
+            assert!(is_simplified(&step.moves), "{} isn't simplified", algorithm);
+        }
+        assert!(is_simplified(&solution.moves()));
+        assert_solves(cube, &solution.moves());
+    }
+}
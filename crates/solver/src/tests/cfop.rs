@@ -0,0 +1,134 @@
+use super::*;
+use crate::cfop::{solve, solve_oll, solve_pll, OLL, PLL};
+use crate::cubie::{CubieCube, CORNERS, EDGES};
+
+/// Every arrangement of four pieces.
+fn permutations<T: Copy>(pieces: [T; 4]) -> Vec<[T; 4]> {
+    let mut result = Vec::new();
+    for a in 0..4 {
+        for b in (0..4).filter(|&b| b != a) {
+            for c in (0..4).filter(|&c| c != a && c != b) {
+                let d = 6 - a - b - c;
+                result.push([pieces[a], pieces[b], pieces[c], pieces[d]]);
+            }
+        }
+    }
+    result
+}
+
+#[test]
+fn test_every_f2l_case() {
+    // The front right pair anywhere in the top layer or its slot, with the rest
+    // of the first two layers solved. The top layer evens out the twist, flip
+    // and parity.
+    for corner in [0, 1, 2, 3, 4] {
+        for twist in 0..3 {
+            for edge in [0, 1, 2, 3, 8] {
+                for flip in 0..2 {
+                    let mut cube = CubieCube::SOLVED;
+                    cube.cp.swap(4, corner);
+                    cube.ep.swap(8, edge);
+                    cube.co[corner] = twist;
+                    cube.eo[edge] = flip;
+                    let spare = if corner == 0 { 1 } else { 0 };
+                    cube.co[spare] = (3 - cube.co.iter().sum::<u8>() % 3) % 3;
+                    let spare = if edge == 0 { 1 } else { 0 };
+                    cube.eo[spare] = cube.eo.iter().sum::<u8>() % 2;
+                    if cube.validate().is_err() {
+                        let spares: Vec<usize> = (0..4).filter(|&e| e != edge).collect();
+                        cube.ep.swap(spares[0], spares[1]);
+                    }
+                    cube.validate().unwrap();
+
+                    let stages = solve(&cube.to_cube());
+                    assert!(stages[0].moves.is_empty());
+                    let mut solved = cube.to_cube();
+                    for &m in &stages[1].moves {
+                        solved.make_move(m);
+                    }
+                    let solved = CubieCube::from_cube(&solved).unwrap();
+                    assert_eq!(solved.cp[4..], CubieCube::SOLVED.cp[4..], "{:?}", cube);
+                    assert_eq!(solved.ep[4..], CubieCube::SOLVED.ep[4..], "{:?}", cube);
+                    assert_eq!(solved.co[4..], [0; 4], "{:?}", cube);
+                    assert_eq!(solved.eo[4..], [0; 8], "{:?}", cube);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_every_oll_case() {
+    for twist in 0..27 {
+        for flip in 0..8 {
+            let mut cube = CubieCube::SOLVED;
+            cube.co[..3].copy_from_slice(&[twist / 9, twist / 3 % 3, twist % 3]);
+            cube.co[3] = (6 - cube.co[..3].iter().sum::<u8>()) % 3;
+            cube.eo[..3].copy_from_slice(&[flip / 4, flip / 2 % 2, flip % 2]);
+            cube.eo[3] = cube.eo[..3].iter().sum::<u8>() % 2;
+
+            let mut oriented = cube.clone();
+            solve_oll(&mut oriented);
+            assert_eq!(oriented.co, [0; 8], "{:?}", cube);
+            assert_eq!(oriented.eo, [0; 12], "{:?}", cube);
+            assert_eq!(oriented.cp[4..], CubieCube::SOLVED.cp[4..]);
+            assert_eq!(oriented.ep[4..], CubieCube::SOLVED.ep[4..]);
+        }
+    }
+}
+
+#[test]
+fn test_every_pll_case() {
+    let corners = permutations([CORNERS[0], CORNERS[1], CORNERS[2], CORNERS[3]]);
+    let edges = permutations([EDGES[0], EDGES[1], EDGES[2], EDGES[3]]);
+    for cp in &corners {
+        for ep in &edges {
+            let mut cube = CubieCube::SOLVED;
+            cube.cp[..4].copy_from_slice(cp);
+            cube.ep[..4].copy_from_slice(ep);
+            if cube.validate().is_err() {
+                continue;
+            }
+            let mut solved = cube.clone();
+            solve_pll(&mut solved);
+            assert_eq!(solved, CubieCube::SOLVED, "{:?}", cube);
+        }
+    }
+}
+
+#[test]
+fn test_algorithms_keep_orientation() {
+    // Every algorithm puts the centres back, so the cube ends up held as it
+    // started.
+    let algs = OLL.iter().chain(PLL.iter().map(|(_, alg)| alg));
+    for alg in algs {
+        let cube = scrambled(&crate::notation::parse(alg).unwrap());
+        assert!(
+            (0..6).all(|i| cube.faces[i].colour() == Cube::SOLVED.faces[i].colour()),
+            "{} turns the cube",
+            alg
+        );
+    }
+}
+
+#[test]
+fn test_solve() {
+    for cube in scrambles().take(3) {
+        let stages = solve(&cube);
+        let names: Vec<_> = stages.iter().map(|stage| stage.name).collect();
+        assert_eq!(names, ["Cross", "F2L", "OLL", "PLL"]);
+        assert!(stages[0].moves.len() <= 8);
+
+        let mut solved = cube.clone();
+        for stage in &stages {
+            for &m in &stage.moves {
+                solved.make_move(m);
+            }
+        }
+        assert!(
+            solved.faces == Cube::SOLVED.faces,
+            "not solved:\n{}",
+            solved
+        );
+    }
+}
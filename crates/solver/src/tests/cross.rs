@@ -0,0 +1,132 @@
+use super::*;
+use crate::cross::{
+    plan_pairs, solve, solve_all, solve_colour_neutral, solve_each_colour, Cross, Plan, Slot,
+};
+use crate::cubie::{CubieCube, Edge};
+
+/// Whether the pair of the slot is solved.
+fn pair_solved(cube: &Cube, slot: Slot) -> bool {
+    let cube = CubieCube::from_cube(cube).unwrap();
+    let (corner, edge) = match slot {
+        Slot::FrontRight => (4, 8),
+        Slot::FrontLeft => (5, 9),
+        Slot::BackLeft => (6, 10),
+        Slot::BackRight => (7, 11),
+    };
+    cube.cp[corner] as usize == corner
+        && cube.co[corner] == 0
+        && cube.ep[edge] as usize == edge
+        && cube.eo[edge] == 0
+}
+
+/// Whether the cross on the bottom face is solved.
+fn cross_solved(cube: &Cube) -> bool {
+    let cube = CubieCube::from_cube(cube).unwrap();
+    cube.ep[4..8] == [Edge::Dr, Edge::Df, Edge::Dl, Edge::Db] && cube.eo[4..8] == [0; 4]
+}
+
+#[test]
+fn test_solved() {
+    for cross in solve_each_colour(&Cube::SOLVED, &Colour::ALL) {
+        assert!(cross.moves.is_empty(), "{:?}", cross);
+    }
+    assert_eq!(
+        solve_colour_neutral(&Cube::SOLVED, &Colour::ALL),
+        Cross {
+            colour: Colour::White,
+            rotation: vec![],
+            moves: vec![],
+        }
+    );
+}
+
+#[test]
+fn test_chooses_cheapest_colour() {
+    let cube = scrambled(&[Move::D]);
+    let cross = solve_colour_neutral(&cube, &[Colour::White, Colour::Yellow]);
+    assert_eq!(
+        cross,
+        Cross {
+            colour: Colour::Yellow,
+            rotation: vec![Move::X2],
+            moves: vec![],
+        }
+    );
+    let cross = solve_colour_neutral(&cube, &[Colour::White]);
+    assert_eq!(cross.moves, [Move::DP]);
+}
+
+#[test]
+fn test_crosses_are_solved() {
+    for cube in scrambles().take(10) {
+        for cross in solve_each_colour(&cube, &Colour::ALL) {
+            let mut cube = cube.clone();
+            for &m in cross.rotation.iter().chain(&cross.moves) {
+                cube.make_move(m);
+            }
+            assert!(cube.face_colour(FaceName::Bottom) == cross.colour);
+            assert!(cross_solved(&cube), "{:?}\n{}", cross, cube);
+            assert!(cross.moves.len() <= 8);
+        }
+    }
+}
+
+#[test]
+fn test_cross_is_shortest() {
+    let cube = scrambled(&[Move::F, Move::R]);
+    assert_eq!(
+        solve_colour_neutral(&cube, &[Colour::White]).moves,
+        [Move::RP, Move::FP]
+    );
+}
+
+#[test]
+fn test_solve_all() {
+    let cube = scrambled(&[Move::R, Move::L]);
+    let mut solutions = solve_all(&cube);
+    solutions.sort_by_key(|moves| format!("{:?}", moves));
+    assert_eq!(solutions, [[Move::LP, Move::RP], [Move::RP, Move::LP]]);
+
+    for cube in scrambles().take(5) {
+        let shortest = solve(&cube);
+        let solutions = solve_all(&cube);
+        assert!(solutions.contains(&shortest));
+        for moves in solutions {
+            assert_eq!(moves.len(), shortest.len());
+            let mut cube = cube.clone();
+            for m in moves {
+                cube.make_move(m);
+            }
+            assert!(cross_solved(&cube), "{}", cube);
+        }
+    }
+}
+
+#[test]
+fn test_plan_pairs() {
+    let cube = scrambled(&[Move::R, Move::U, Move::RP, Move::F]);
+    let plans = plan_pairs(&cube, &[Colour::White], 1, 4);
+    assert!(plans.contains(&Plan {
+        colour: Colour::White,
+        slots: vec![Slot::FrontRight],
+        rotation: vec![],
+        moves: vec![Move::FP, Move::R, Move::UP, Move::RP],
+    }));
+
+    for cube in scrambles().take(3) {
+        for pairs in [1, 2] {
+            for plan in plan_pairs(&cube, &[Colour::White, Colour::Yellow], pairs, 7) {
+                assert_eq!(plan.slots.len(), pairs);
+                assert!(plan.moves.len() <= 7);
+                let mut cube = cube.clone();
+                for &m in plan.rotation.iter().chain(&plan.moves) {
+                    cube.make_move(m);
+                }
+                assert!(cross_solved(&cube), "{:?}\n{}", plan, cube);
+                for &slot in &plan.slots {
+                    assert!(pair_solved(&cube, slot), "{:?}\n{}", plan, cube);
+                }
+            }
+        }
+    }
+}
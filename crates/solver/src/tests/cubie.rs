@@ -0,0 +1,89 @@
+use super::*;
+use crate::cubie::*;
+
+#[test]
+fn test_from_solved_cube() {
+    assert_eq!(CubieCube::from_cube(&Cube::SOLVED), Ok(CubieCube::SOLVED));
+}
+
+#[test]
+fn test_moves_match_facelets() {
+    let moves = [
+        Move::R,
+        Move::U,
+        Move::FP,
+        Move::L,
+        Move::D,
+        Move::BP,
+        Move::F,
+    ];
+    let mut cube = Cube::SOLVED;
+    let mut cubie = CubieCube::SOLVED;
+    for m in moves.into_iter().chain(ALL_MOVES) {
+        cube.make_move(m);
+        cubie.make_move(m);
+        assert_eq!(CubieCube::from_cube(&cube), Ok(cubie.clone()), "{:?}", m);
+    }
+    assert_eq!(cubie.validate(), Ok(()));
+}
+
+#[test]
+fn test_moves_match_facelets_relative_to_centres() {
+    let base = scrambled(&[Move::R, Move::U, Move::F, Move::LP, Move::D, Move::BP]);
+    for m in every_move() {
+        let mut cube = base.clone();
+        cube.make_move(m);
+        let mut cubie = CubieCube::from_cube(&base).unwrap();
+        cubie.make_move(m);
+        assert_eq!(CubieCube::from_cube(&cube), Ok(cubie), "{:?}", m);
+    }
+}
+
+#[test]
+fn test_coordinates_round_trip() {
+    let mut cube = CubieCube::SOLVED;
+    for slice in 0..495 {
+        cube.set_slice(slice);
+        assert_eq!(cube.slice(), slice);
+    }
+    for twist in 0..2187 {
+        cube.set_twist(twist);
+        assert_eq!(cube.twist(), twist);
+    }
+    for flip in 0..2048 {
+        cube.set_flip(flip);
+        assert_eq!(cube.flip(), flip);
+    }
+    for perm in (0..40320).step_by(7) {
+        cube.set_corner_perm(perm);
+        assert_eq!(cube.corner_perm(), perm);
+    }
+}
+
+#[test]
+fn test_unsolvable() {
+    let mut cube = CubieCube::SOLVED;
+    cube.co[0] = 1;
+    assert!(cube.validate().is_err());
+
+    let mut cube = CubieCube::SOLVED;
+    cube.ep.swap(0, 1);
+    assert!(cube.validate().is_err());
+}
+
+#[test]
+fn test_to_cube() {
+    assert_eq!(CubieCube::SOLVED.to_cube().faces, Cube::SOLVED.faces);
+
+    for mut cube in scrambles().take(10) {
+        let mut cubie = CubieCube::from_cube(&cube).unwrap();
+        assert_eq!(cubie.to_cube().faces, cube.faces);
+
+        // Moves made on either representation agree.
+        for m in ALL_MOVES.into_iter().chain(HALF_TURNS) {
+            cube.make_move(m);
+            cubie.make_move(m);
+        }
+        assert_eq!(cubie.to_cube().faces, cube.faces);
+    }
+}
@@ -0,0 +1,23 @@
+use super::*;
+
+#[test]
+fn test_solve_solved() {
+    assert!(crate::kociemba::solve(&Cube::SOLVED).is_empty());
+}
+
+#[test]
+fn test_solve() {
+    for cube in scrambles().take(10) {
+        let moves = crate::kociemba::solve(&cube);
+        assert_solves(cube, &moves);
+    }
+}
+
+#[test]
+fn test_max_length() {
+    // R U F needs three moves, so it can't be done in two.
+    let cube = scrambled(&[Move::R, Move::U, Move::F]);
+    assert!(crate::kociemba::solve_with_max_length(&cube, 2).is_none());
+    let moves = crate::kociemba::solve_with_max_length(&cube, 3).unwrap();
+    assert_eq!(moves, [Move::FP, Move::UP, Move::RP]);
+}
@@ -0,0 +1,144 @@
+use super::*;
+use crate::algorithm::Algorithm;
+use crate::cubie::CubieCube;
+use crate::metric::{etm, htm, qtm, stm, Metric};
+use crate::notation::parse;
+use crate::search::Turns;
+
+#[test]
+fn test_count() {
+    let moves = parse("R U2 M' E2 r f2 x y2").unwrap();
+    assert_eq!(htm(&moves), 8);
+    assert_eq!(qtm(&moves), 12);
+    assert_eq!(stm(&moves), 6);
+    assert_eq!(etm(&moves), 8);
+
+    let sune: Algorithm = "R U R' U R U2 R'".parse().unwrap();
+    assert_eq!(sune.count(Metric::Htm), 7);
+    assert_eq!(sune.count(Metric::Qtm), 8);
+    assert!(htm(&[]) == 0 && qtm(&[]) == 0);
+}
+
+#[test]
+#[ignore = "builds the full pattern databases, which takes minutes unoptimised"]
+fn test_optimal_in() {
+    let cube = scrambled(&parse("R U R' U'").unwrap());
+    for metric in Metric::ALL {
+        let moves = crate::optimal::solve_in(&cube, metric);
+        assert_solves(cube.clone(), &moves);
+        assert_eq!(metric.count(&moves), 4);
+    }
+
+    // Three face turns, but four quarter turns.
+    let cube = scrambled(&parse("R2 U F'").unwrap());
+    assert_eq!(
+        crate::optimal::solve_with_max_length_in(&cube, 3, Metric::Qtm),
+        None
+    );
+    let moves = crate::optimal::solve_with_max_length_in(&cube, 4, Metric::Qtm).unwrap();
+    assert_eq!(qtm(&moves), 4);
+
+    let cube = scrambled(&parse("R U F' L2 D").unwrap());
+    for metric in Metric::ALL {
+        let moves = solve_with_metric(cube.clone(), Method::Optimal, metric).unwrap();
+        assert_solves(cube.clone(), &moves);
+    }
+}
+
+#[test]
+fn test_optimal_slice_turns() {
+    // Six face turns, but four slice turns.
+    let cube = scrambled(&parse("M2 U2 M2 U2").unwrap());
+    let moves = super::optimal::solve_with_corner_bound(&cube, 6, Metric::Htm).unwrap();
+    assert_solves(cube.clone(), &moves);
+    assert_eq!(htm(&moves), 6);
+    for metric in [Metric::Stm, Metric::Etm] {
+        let moves = super::optimal::solve_with_corner_bound(&cube, 6, metric).unwrap();
+        assert_solves(cube.clone(), &moves);
+        assert_eq!(metric.count(&moves), 4, "{:?}", moves);
+    }
+}
+
+#[test]
+fn test_slice_turn_moves() {
+    // Any two moves of the search, made as the moves it gives, leave the
+    // pieces where its face turns do and the cube held as it started.
+    let turns = Turns::new(Metric::Stm);
+    assert_eq!(turns.len(), 27);
+    for first in 0..turns.len() {
+        for second in 0..turns.len() {
+            let path = [first, second];
+            let mut cube = Cube::SOLVED;
+            for m in turns.to_moves(&path) {
+                cube.make_move(m);
+            }
+            let mut pieces = CubieCube::SOLVED;
+            for &m in &path {
+                for &turn in turns.face_turns(m) {
+                    pieces.make_face_turn(turn);
+                }
+            }
+            assert_eq!(cube.faces, pieces.to_cube().faces, "{:?}", path);
+        }
+    }
+
+    // The rotation that ends a slice turn only counts in ETM.
+    assert_eq!(turns.to_moves(&[22]), [Move::M2, Move::X2]);
+    assert_eq!(turns.restore_cost(&[22]), 0);
+    assert_eq!(Turns::new(Metric::Etm).restore_cost(&[22]), 1);
+    assert_eq!(Turns::new(Metric::Htm).len(), 18);
+}
+
+#[test]
+fn test_two_phase_in() {
+    let mut cubes = scrambles();
+    for metric in [Metric::Qtm, Metric::Stm, Metric::Etm] {
+        let cube = cubes.next().unwrap();
+        let moves = crate::kociemba::solve_in(&cube, metric);
+        assert_solves(cube, &moves);
+        let cube = cubes.next().unwrap();
+        let moves = solve_with_metric(cube.clone(), Method::TwoPhase, metric).unwrap();
+        assert_solves(cube, &moves);
+    }
+
+    let cube = scrambled(&parse("M2 U2 M2 U2").unwrap());
+    let moves = crate::kociemba::solve_in(&cube, Metric::Stm);
+    assert_solves(cube, &moves);
+    assert_eq!(stm(&moves), 4, "{:?}", moves);
+}
+
+#[test]
+fn test_every_metric() {
+    // Every method solves the cube in every metric, and the half turn metric
+    // gives the same solutions as `solve_with`. The optimal search is checked
+    // in `test_optimal_in`.
+    let cube = scrambled(&parse("R U F' L2 D").unwrap());
+    let methods = [
+        Method::LayerByLayer,
+        Method::TwoPhase,
+        Method::Thistlethwaite,
+        Method::Cfop,
+        Method::Roux,
+        Method::Zz,
+        Method::Petrus,
+    ];
+    for method in methods {
+        let moves = solve_with_metric(cube.clone(), method, Metric::Htm).unwrap();
+        assert_eq!(Ok(moves.clone()), solve_with(cube.clone(), method));
+        for metric in Metric::ALL {
+            let moves = solve_with_metric(cube.clone(), method, metric).unwrap();
+            assert_solves(cube.clone(), &moves);
+        }
+    }
+}
+
+#[test]
+fn test_cross_in() {
+    // Each cross is the shortest in its own metric.
+    for cube in scrambles().take(3) {
+        let moves = crate::cross::solve(&cube);
+        let quarter_turns = crate::cross::solve_in(&cube, Metric::Qtm);
+        assert!(htm(&moves) <= htm(&quarter_turns));
+        assert!(qtm(&quarter_turns) <= qtm(&moves));
+    }
+}
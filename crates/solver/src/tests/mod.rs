@@ -1,5 +1,28 @@
+mod algorithm;
+mod blindfold;
+mod cfop;
+mod cross;
+mod cubie;
+mod kociemba;
+mod metric;
+mod notation;
+mod optimal;
+mod petrus;
+mod roux;
+mod scramble;
+mod thistlethwaite;
+mod validate;
+mod zz;
+
 use super::*;
 
+use Colour::Blue as B;
+use Colour::Green as G;
+use Colour::Orange as O;
+use Colour::Red as R;
+use Colour::White as W;
+use Colour::Yellow as Y;
+
 // Using new cube
 
 fn test_move(m: Move, output: [Colour; 54]) {
@@ -237,3 +260,286 @@ fn test_right_prime() {
     ];
     test_move(Move::RP, output);
 }
+
+const ALL_MOVES: [Move; 12] = [
+    Move::F,
+    Move::B,
+    Move::U,
+    Move::D,
+    Move::L,
+    Move::R,
+    Move::FP,
+    Move::BP,
+    Move::UP,
+    Move::DP,
+    Move::LP,
+    Move::RP,
+];
+
+const HALF_TURNS: [Move; 6] = [Move::F2, Move::B2, Move::U2, Move::D2, Move::L2, Move::R2];
+
+#[rustfmt::skip]
+const SLICE_TURNS: [Move; 9] = [
+    Move::M, Move::E, Move::S,
+    Move::MP, Move::EP, Move::SP,
+    Move::M2, Move::E2, Move::S2,
+];
+
+#[rustfmt::skip]
+const WIDE_TURNS: [Move; 18] = [
+    Move::Fw, Move::Bw, Move::Uw, Move::Dw, Move::Lw, Move::Rw,
+    Move::FwP, Move::BwP, Move::UwP, Move::DwP, Move::LwP, Move::RwP,
+    Move::Fw2, Move::Bw2, Move::Uw2, Move::Dw2, Move::Lw2, Move::Rw2,
+];
+
+#[rustfmt::skip]
+const ROTATIONS: [Move; 9] = [
+    Move::X, Move::Y, Move::Z,
+    Move::XP, Move::YP, Move::ZP,
+    Move::X2, Move::Y2, Move::Z2,
+];
+
+/// Every move, of every kind.
+fn every_move() -> impl Iterator<Item = Move> {
+    ALL_MOVES
+        .into_iter()
+        .chain(HALF_TURNS)
+        .chain(SLICE_TURNS)
+        .chain(WIDE_TURNS)
+        .chain(ROTATIONS)
+}
+
+#[test]
+fn test_update_pos_after_move() {
+    for m in every_move() {
+        let mut cube = Cube::SOLVED;
+        cube.make_move(m);
+        for (edge, pos) in Cube::SOLVED.edges() {
+            let mut tracked = pos;
+            update_pos_after_move(&mut tracked, m);
+            assert_eq!(tracked, cube.find_edge(edge), "{:?} {:?}", m, edge);
+        }
+        for (corner, pos) in Cube::SOLVED.corners() {
+            let mut tracked = pos;
+            update_pos_after_move(&mut tracked, m);
+            assert_eq!(tracked, cube.find_corner(corner), "{:?} {:?}", m, corner);
+        }
+    }
+}
+
+fn scrambled(moves: &[Move]) -> Cube {
+    let mut cube = Cube::SOLVED;
+    for &m in moves {
+        cube.make_move(m);
+    }
+    cube
+}
+
+/// Deterministic scrambles, picked by walking through the moves with a
+/// simple LCG.
+fn scrambles() -> impl Iterator<Item = Cube> {
+    let mut state: u32 = 12345;
+    (0..50).map(move |_| {
+        let moves: Vec<Move> = (0..30)
+            .map(|_| {
+                state = state.wrapping_mul(1103515245).wrapping_add(12345);
+                ALL_MOVES[(state >> 16) as usize % ALL_MOVES.len()]
+            })
+            .collect();
+        scrambled(&moves)
+    })
+}
+
+fn assert_solves(cube: Cube, moves: &[Move]) {
+    let mut cube = cube;
+    for &m in moves {
+        cube.make_move(m);
+    }
+    assert!(cube.faces == Cube::SOLVED.faces, "not solved:\n{}", cube);
+}
+
+#[test]
+fn test_solve_solved() {
+    assert!(solve(Cube::SOLVED).is_empty());
+}
+
+#[test]
+fn test_solve() {
+    for cube in scrambles() {
+        let moves = solve(cube.clone());
+        assert_solves(cube, &moves);
+    }
+}
+#[test]
+fn test_half_turns() {
+    let base = scrambled(&[Move::R, Move::U, Move::F, Move::LP, Move::D, Move::BP]);
+    for (half, quarter) in
+        HALF_TURNS
+            .into_iter()
+            .zip([Move::F, Move::B, Move::U, Move::D, Move::L, Move::R])
+    {
+        let mut cube = base.clone();
+        cube.make_move(half);
+        let mut expected = base.clone();
+        expected.make_move(quarter);
+        expected.make_move(quarter);
+        assert!(cube.faces == expected.faces, "{:?}", half);
+
+        cube.make_move(half.reverse());
+        assert!(cube.faces == base.faces, "{:?}", half);
+    }
+}
+
+#[test]
+fn test_slice_turns() {
+    // Turning a slice along with the faces either side of it turns the whole
+    // cube, leaving every face a single colour.
+    for moves in [
+        [Move::R, Move::MP, Move::LP],
+        [Move::U, Move::EP, Move::DP],
+        [Move::F, Move::S, Move::BP],
+    ] {
+        let cube = scrambled(&moves);
+        assert!(cube.is_solved(), "{:?}", moves);
+        assert!(cube.faces != Cube::SOLVED.faces, "{:?}", moves);
+    }
+
+    // M, E and S turn the same way as L, D and F.
+    let cube = scrambled(&[Move::M]);
+    assert_eq!(cube[Bottom].0[4], Cube::SOLVED[Front].0[4]);
+    let cube = scrambled(&[Move::E]);
+    assert_eq!(cube[Right].0[4], Cube::SOLVED[Front].0[4]);
+    let cube = scrambled(&[Move::S]);
+    assert_eq!(cube[Right].0[4], Cube::SOLVED[Top].0[4]);
+}
+
+#[test]
+fn test_wide_turns() {
+    let base = scrambled(&[Move::R, Move::U, Move::F, Move::LP, Move::D, Move::BP]);
+    for (wide, parts) in [
+        (Move::Rw, [Move::R, Move::MP]),
+        (Move::LwP, [Move::LP, Move::MP]),
+        (Move::Uw2, [Move::U2, Move::E2]),
+        (Move::Dw, [Move::D, Move::E]),
+        (Move::FwP, [Move::FP, Move::SP]),
+        (Move::Bw, [Move::B, Move::SP]),
+    ] {
+        let mut cube = base.clone();
+        cube.make_move(wide);
+        let mut expected = base.clone();
+        for part in parts {
+            expected.make_move(part);
+        }
+        assert!(cube.faces == expected.faces, "{:?}", wide);
+    }
+}
+
+#[test]
+fn test_rotations() {
+    // x, y and z turn the same way as R, U and F, taking the front, right and
+    // top faces to the top, front and right respectively.
+    for (rotation, from, to) in [
+        (Move::X, Front, Top),
+        (Move::Y, Right, Front),
+        (Move::Z, Top, Right),
+    ] {
+        let base = scrambled(&[Move::R, Move::U, Move::F]);
+        let mut cube = base.clone();
+        cube.make_move(rotation);
+        assert_eq!(cube[to].colour(), base[from].colour(), "{:?}", rotation);
+    }
+}
+
+#[test]
+fn test_solve_any_orientation() {
+    for (cube, rotations) in scrambles().zip([
+        [Move::X, Move::Y],
+        [Move::ZP, Move::Y2],
+        [Move::X2, Move::YP],
+    ]) {
+        let mut rotated = cube;
+        for rotation in rotations {
+            rotated.make_move(rotation);
+        }
+        let moves = solve(rotated.clone());
+        assert_solves(rotated, &moves);
+    }
+}
+
+#[test]
+fn test_colour_schemes() {
+    let custom = ColourScheme {
+        top: B,
+        left: R,
+        front: Y,
+        right: G,
+        back: O,
+        bottom: W,
+    };
+    for scheme in [ColourScheme::WESTERN, ColourScheme::JAPANESE, custom] {
+        let solved = scheme.solved();
+        assert_eq!(ColourScheme::of(&solved), scheme);
+
+        let mut cube = solved.clone();
+        for m in [
+            Move::R,
+            Move::U,
+            Move::FP,
+            Move::L2,
+            Move::D,
+            Move::BP,
+            Move::X,
+            Move::YP,
+        ] {
+            cube.make_move(m);
+        }
+        // The presets are recognised, but other schemes are solved as held.
+        let default = if scheme == custom {
+            ColourScheme::of(&cube).solved()
+        } else {
+            solved.clone()
+        };
+        for (moves, expected) in [
+            (solve(cube.clone()), default),
+            (solve_with_scheme(cube.clone(), scheme), solved),
+        ] {
+            let mut cube = cube.clone();
+            for m in moves {
+                cube.make_move(m);
+            }
+            assert!(cube.faces == expected.faces, "not solved:\n{}", cube);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "centres can't be rotated to match the colour scheme")]
+fn test_solve_with_wrong_scheme() {
+    solve_with_scheme(Cube::SOLVED, ColourScheme::JAPANESE);
+}
+
+#[test]
+fn test_move_inverse() {
+    // From the solved cube, a reversed strip of stickers is indistinguishable
+    // from a correct one, so check the moves against each other from a
+    // scrambled cube.
+    let base = scrambled(&[Move::R, Move::U, Move::F, Move::LP, Move::D, Move::BP]);
+    for m in every_move() {
+        let mut cube = base.clone();
+        cube.make_move(m);
+        cube.make_move(m.reverse());
+        assert!(cube.faces == base.faces, "{:?}", m);
+
+        let mut cube = base.clone();
+        for _ in 0..4 {
+            cube.make_move(m);
+        }
+        assert!(cube.faces == base.faces, "{:?}", m);
+
+        let mut cube = base.clone();
+        cube.make_move(m);
+        for (corner, _) in cube.corners() {
+            assert!(Cube::SOLVED.corners().any(|(c, _)| c == corner), "{:?}", m);
+        }
+    }
+}
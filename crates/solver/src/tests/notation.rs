@@ -0,0 +1,122 @@
+use super::*;
+use crate::notation::{format, parse, ParseMoveError, MAX_DEPTH, MAX_MOVES};
+
+#[test]
+fn test_parse() {
+    use Move::*;
+    assert_eq!(parse("R U R' U' F2"), Ok(vec![R, U, RP, UP, F2]));
+    assert_eq!(
+        parse("  R\tU’\n\nF2' B′ D` L‘ "),
+        Ok(vec![R, UP, F2, BP, DP, LP])
+    );
+    assert_eq!(parse("RUR'U'"), Ok(vec![R, U, RP, UP]));
+    assert_eq!(parse(""), Ok(vec![]));
+    assert_eq!(
+        parse("M2 E' S r u' Fw2 Rw'"),
+        Ok(vec![M2, EP, S, Rw, UwP, Fw2, RwP])
+    );
+    assert_eq!(parse("x y' z2 Rx"), Ok(vec![X, YP, Z2, R, X]));
+    assert_eq!("R'".parse(), Ok(RP));
+}
+
+#[test]
+fn test_parse_errors() {
+    let err = |position, token: &str| {
+        Err(ParseMoveError {
+            position,
+            token: token.to_string(),
+            expected: None,
+        })
+    };
+    assert_eq!(parse("R U X' F"), err(4, "X'"));
+    assert_eq!(parse("R U3 F"), err(2, "U3"));
+    assert_eq!(parse("R Uq F"), err(2, "Uq"));
+    assert_eq!(parse("RUX"), err(1, "UX"));
+    assert_eq!(parse("R ’"), err(2, "’"));
+    assert_eq!(
+        parse("R U X").unwrap_err().to_string(),
+        "unknown move `X` at position 4"
+    );
+    assert_eq!("R2".parse(), Ok(Move::R2));
+    assert!("R U".parse::<Move>().is_err());
+}
+
+#[test]
+fn test_parse_groups() {
+    let expand = |s| format(&parse(s).unwrap());
+    assert_eq!(expand("[R U R', D]"), "R U R' D R U' R' D'");
+    assert_eq!(expand("[F: R U R' U']"), "F R U R' U' F'");
+    assert_eq!(expand("[R: [U, R' D R]]"), "R U R' D R U' R' D' R R'");
+    assert_eq!(expand("(R U R' U')3"), "R U R' U' R U R' U' R U R' U'");
+    assert_eq!(expand("(R U)2' F"), "U' R' U' R' F");
+    assert_eq!(expand("[R, U]2"), "R U R' U' R U R' U'");
+    assert_eq!(expand("[M', U2]'"), "U2 M' U2 M");
+    assert_eq!(expand("(R U)0 F"), "F");
+    assert_eq!(
+        expand("R U // a comment\n[R,U] // another, [with brackets]"),
+        "R U R U R' U'"
+    );
+
+    // The expansion can be applied like any other moves.
+    let sexy = parse("(R U R' U')6").unwrap();
+    assert_solves(scrambled(&sexy), &[]);
+}
+
+#[test]
+fn test_parse_group_errors() {
+    let err = |position, token: &str, expected| {
+        Err(ParseMoveError {
+            position,
+            token: token.to_string(),
+            expected,
+        })
+    };
+    assert_eq!(parse("[R U R' D]"), err(9, "]", Some(',')));
+    assert_eq!(parse("[R, U"), err(5, "", Some(']')));
+    assert_eq!(parse("(R U]"), err(4, "]", Some(')')));
+    assert_eq!(parse("R U) F"), err(3, ")", None));
+    assert_eq!(parse("[R, X]"), err(4, "X", None));
+    // Counts that would expand beyond `MAX_MOVES`, or overflow.
+    assert_eq!(
+        parse("(R)9999999999999999"),
+        err(3, "9999999999999999", None)
+    );
+    assert_eq!(parse("(R U)50001 F"), err(5, "50001", None));
+    assert_eq!(parse("((R)1000)1000"), err(9, "1000", None));
+    assert_eq!(parse("(R U)50000").unwrap().len(), MAX_MOVES);
+    // Brackets nested too deep, rather than overflowing the stack.
+    let nested = |depth| "(".repeat(depth) + "R" + &")".repeat(depth);
+    assert_eq!(parse(&nested(MAX_DEPTH)), Ok(vec![Move::R]));
+    assert_eq!(parse(&nested(MAX_DEPTH + 1)), err(MAX_DEPTH, "(", None));
+    assert_eq!(parse(&nested(200_000)), err(MAX_DEPTH, "(", None));
+    let nested = "[R: ".repeat(MAX_DEPTH + 1) + "U" + &"]".repeat(MAX_DEPTH + 1);
+    assert_eq!(parse(&nested), err(4 * MAX_DEPTH, "[", None));
+    assert_eq!(
+        parse("[R, U").unwrap_err().to_string(),
+        "expected `]` at position 5, found the end"
+    );
+    assert_eq!(
+        parse("(R U]").unwrap_err().to_string(),
+        "expected `)` at position 4, found `]`"
+    );
+}
+
+#[test]
+fn test_format() {
+    use Move::*;
+    assert_eq!(format(&[R, U, RP, UP, F2]), "R U R' U' F2");
+    assert_eq!(format(&[BP, BP, B2]), "B' B' B2");
+    assert_eq!(format(&[]), "");
+    assert_eq!(format(&[M, SP, E2, Rw, LwP, Dw2]), "M S' E2 r l' d2");
+}
+
+#[test]
+fn test_round_trip() {
+    let notation = "R U R' U' F2 L' D2 B M' E2 S r' u2 f x y' z2";
+    assert_eq!(format(&parse(notation).unwrap()), notation);
+
+    for cube in scrambles().take(10) {
+        let moves = crate::kociemba::solve(&cube);
+        assert_eq!(parse(&format(&moves)), Ok(moves));
+    }
+}
@@ -0,0 +1,153 @@
+use super::*;
+use crate::cubie::{CubieCube, N_CORNER_PERM, N_MOVES, N_TWIST};
+use crate::metric::Metric;
+use crate::notation::parse;
+use crate::optimal::{
+    placement_from_index, placement_index, search, tables, Coords, Distances, N_PLACEMENT,
+};
+use crate::search::{distance_table, move_coord, Turns};
+
+/// Finds a shortest solution in `metric` of at most `max_length` moves,
+/// bounding the search with small tables of the corner permutation and twist
+/// alone. They build at once but bound the search poorly, so this is only
+/// quick for cubes a few moves from solved.
+pub(crate) fn solve_with_corner_bound(
+    cube: &Cube,
+    max_length: usize,
+    metric: Metric,
+) -> Option<Vec<Move>> {
+    let (t, turns) = (tables(), Turns::new(metric));
+    let moves: Vec<usize> = (0..turns.len()).collect();
+    let corner_perm = distance_table(N_CORNER_PERM, &[0], &moves, turns.costs(), |cp, m| {
+        move_coord(&t.corner_perm_move, cp, turns.face_turns(m))
+    });
+    let twist = distance_table(N_TWIST, &[0], &moves, turns.costs(), |twist, m| {
+        move_coord(&t.twist_move, twist, turns.face_turns(m))
+    });
+    let bound = |coords: &Coords| corner_perm[coords.corner_perm].max(twist[coords.twist]) as usize;
+    search(cube, max_length, &turns, u64::MAX, bound).unwrap()
+}
+
+#[test]
+fn test_solve_short() {
+    // R U R' U' can't be shortened.
+    let cube = scrambled(&[Move::R, Move::U, Move::RP, Move::UP]);
+    let moves = solve_with_corner_bound(&cube, 20, Metric::Htm).unwrap();
+    assert_eq!(moves, [Move::U, Move::R, Move::UP, Move::RP]);
+
+    let cube = scrambled(&[Move::F, Move::L, Move::L, Move::D, Move::B]);
+    let moves = solve_with_corner_bound(&cube, 20, Metric::Htm).unwrap();
+    assert_eq!(moves, [Move::BP, Move::DP, Move::L2, Move::FP]);
+    assert!(solve_with_corner_bound(&cube, 3, Metric::Htm).is_none());
+    assert_eq!(
+        solve_with_corner_bound(&Cube::SOLVED, 20, Metric::Htm),
+        Some(vec![])
+    );
+}
+
+#[test]
+fn test_placement_index() {
+    for index in 0..N_PLACEMENT {
+        let positions = placement_from_index(index);
+        assert!(positions.iter().all(|&p| p < 12));
+        for i in 0..6 {
+            assert!(!positions[..i].contains(&positions[i]), "{:?}", positions);
+        }
+        assert_eq!(placement_index(&positions), index);
+    }
+}
+
+#[test]
+fn test_coords_make_face_turns() {
+    // The edge groups' move table must agree with turning the cubies.
+    for cube in scrambles() {
+        let cube = CubieCube::from_solvable_cube(&cube);
+        let coords = Coords::from_cubie(&cube);
+        for m in 0..N_MOVES {
+            let mut turned = cube.clone();
+            turned.make_face_turn(m);
+            assert_eq!(
+                coords.make_face_turns(tables(), &[m]),
+                Coords::from_cubie(&turned),
+                "{}",
+                m
+            );
+        }
+    }
+}
+
+#[test]
+fn test_distances_packing() {
+    let size = 101;
+    let mut packed = Distances::new(size);
+    let mut unpacked = vec![0xf; size];
+    for step in 0..4 * size {
+        // Visit the entries out of order, so neighbours in a byte are set
+        // both before and after each other.
+        let index = step * 37 % size;
+        let distance = (step * 7 % 16) as u8;
+        packed.set(index, distance);
+        unpacked[index] = distance;
+        for (i, &d) in unpacked.iter().enumerate() {
+            assert_eq!(packed.get(i), d, "entry {} after setting {}", i, index);
+        }
+    }
+}
+
+#[test]
+#[ignore = "builds the full pattern databases, which takes minutes unoptimised"]
+fn test_solve_solved() {
+    assert!(crate::optimal::solve(&Cube::SOLVED).is_empty());
+}
+
+#[test]
+#[ignore = "builds the full pattern databases, which takes minutes unoptimised"]
+fn test_solve_is_shortest() {
+    // R U R' U' can't be shortened.
+    let cube = scrambled(&[Move::R, Move::U, Move::RP, Move::UP]);
+    let moves = crate::optimal::solve(&cube);
+    assert_eq!(moves, [Move::U, Move::R, Move::UP, Move::RP]);
+
+    let cube = scrambled(&[Move::F, Move::L, Move::L, Move::D, Move::B]);
+    let moves = crate::optimal::solve(&cube);
+    assert_eq!(moves, [Move::BP, Move::DP, Move::L2, Move::FP]);
+}
+
+#[test]
+#[ignore = "builds the full pattern databases, which takes minutes unoptimised"]
+fn test_solve() {
+    #[rustfmt::skip]
+    let scramble = [
+        Move::R, Move::DP, Move::F, Move::U, Move::LP, Move::B, Move::R,
+    ];
+    let cube = scrambled(&scramble);
+    let moves = crate::optimal::solve(&cube);
+    assert_solves(cube.clone(), &moves);
+    assert!(moves.len() <= scramble.len());
+    assert!(crate::optimal::solve_with_max_length(&cube, 6).is_none());
+}
+
+#[test]
+#[ignore = "builds the full pattern databases, which takes minutes unoptimised"]
+fn test_solve_deep() {
+    // Deep enough that the databases alone are far from the real distance.
+    let scramble = parse("R U2 D' B D' F2 L' U2 F' D2 B' R2").unwrap();
+    let cube = scrambled(&scramble);
+    let moves = crate::optimal::solve(&cube);
+    assert_solves(cube.clone(), &moves);
+    assert!(moves.len() <= scramble.len());
+    assert!(crate::optimal::solve_with_max_length(&cube, moves.len() - 1).is_none());
+}
+
+#[test]
+#[ignore = "builds the full pattern databases, which takes minutes unoptimised"]
+fn test_gives_up() {
+    // A random state is far too deep to search in a hundred thousand nodes.
+    let cube = crate::scramble::random_cube(&mut crate::scramble::Rng::new(3)).to_cube();
+    assert_eq!(
+        crate::optimal::solve_with_max_nodes(&cube, Metric::Htm, 100_000),
+        Err(SolveError::OutOfNodes { nodes: 100_000 })
+    );
+    let moves = crate::optimal::solve_with_max_nodes(&scrambled(&[Move::R]), Metric::Htm, 100_000);
+    assert_eq!(moves, Ok(vec![Move::RP]));
+}
@@ -0,0 +1,112 @@
+use super::*;
+use crate::cubie::{CubieCube, Edge};
+use crate::petrus::{
+    block_2x2x2_solved, block_2x2x3_solved, edges_oriented, f2l_solved, solve,
+    solve_with_max_lengths,
+};
+
+#[test]
+fn test_goals() {
+    let goals = [
+        block_2x2x2_solved,
+        block_2x2x3_solved,
+        edges_oriented,
+        f2l_solved,
+    ];
+    assert!(goals.iter().all(|goal| goal(&Cube::SOLVED)));
+    // U keeps the first two layers, but F breaks the 2x2x3 block and R U R'
+    // only the rest of the first two layers.
+    assert!(goals.iter().all(|goal| goal(&scrambled(&[Move::U]))));
+    let cube = scrambled(&[Move::F]);
+    assert!(block_2x2x2_solved(&cube) && !block_2x2x3_solved(&cube));
+    let cube = scrambled(&[Move::R, Move::U, Move::RP]);
+    assert!(edges_oriented(&cube) && !f2l_solved(&cube));
+    // F2 B2 flips no edges, but breaks the 2x2x2 block that the edge
+    // orientation stage keeps.
+    let cube = scrambled(&[Move::F2, Move::B2]);
+    assert!(!block_2x2x2_solved(&cube) && !edges_oriented(&cube));
+    // Flipping UF and UR keeps the 2x2x3 block.
+    let mut cube = CubieCube::SOLVED;
+    cube.eo[Edge::Uf as usize] = 1;
+    cube.eo[Edge::Ur as usize] = 1;
+    let cube = cube.to_cube();
+    assert!(block_2x2x3_solved(&cube) && !edges_oriented(&cube));
+}
+
+#[test]
+fn test_solve() {
+    let goals = [
+        block_2x2x2_solved,
+        block_2x2x3_solved,
+        edges_oriented,
+        f2l_solved,
+    ];
+    let ru = [Move::R, Move::RP, Move::R2, Move::U, Move::UP, Move::U2];
+    for cube in scrambles().take(3) {
+        let stages = solve(&cube).unwrap();
+        let names: Vec<_> = stages.iter().map(|stage| stage.name).collect();
+        assert_eq!(
+            names,
+            ["2x2x2 block", "2x2x3 block", "EO", "F2L", "OLL", "PLL"]
+        );
+
+        let mut partial = cube.clone();
+        for (stage, goal) in stages.iter().zip(goals) {
+            for &m in &stage.moves {
+                partial.make_move(m);
+            }
+            assert!(goal(&partial), "{} not solved", stage.name);
+        }
+        assert!(stages[3].moves.iter().all(|m| ru.contains(m)));
+
+        let moves: Vec<Move> = stages.into_iter().flat_map(|stage| stage.moves).collect();
+        assert_solves(cube, &moves);
+    }
+}
+
+#[test]
+fn test_every_eo_case() {
+    let free = [
+        Edge::Ur,
+        Edge::Uf,
+        Edge::Ul,
+        Edge::Ub,
+        Edge::Dr,
+        Edge::Fr,
+        Edge::Br,
+    ];
+    // Every flip of the edges outside the 2x2x3 block, with the U layer in
+    // each of its four places.
+    for flip in 0..1u32 << (free.len() - 1) {
+        let mut flipped = CubieCube::SOLVED;
+        for (i, &edge) in free[..free.len() - 1].iter().enumerate() {
+            flipped.eo[edge as usize] = (flip >> i & 1) as u8;
+        }
+        flipped.eo[Edge::Br as usize] = (flip.count_ones() % 2) as u8;
+        let mut cube = flipped.to_cube();
+        for _ in 0..4 {
+            cube.make_move(Move::U);
+            let stages = solve(&cube).unwrap();
+            assert!(stages[0].moves.is_empty() && stages[1].moves.is_empty());
+            let mut oriented = cube.clone();
+            for &m in &stages[2].moves {
+                oriented.make_move(m);
+            }
+            assert!(edges_oriented(&oriented), "EO not solved:\n{}", cube);
+        }
+    }
+}
+
+#[test]
+fn test_stage_too_long() {
+    let cube = scrambled(&[Move::L, Move::B]);
+    assert_eq!(
+        solve_with_max_lengths(&cube, [1, 14, 12, 20]),
+        Err(SolveError::StageTooLong {
+            stage: "2x2x2 block",
+            max_length: 1,
+        })
+    );
+    let stages = solve_with_max_lengths(&cube, [2, 0, 0, 0]).unwrap();
+    assert_eq!(stages[0].moves, [Move::BP, Move::LP]);
+}
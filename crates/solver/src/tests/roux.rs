@@ -0,0 +1,173 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use super::*;
+use crate::cubie::{CubieCube, CORNERS};
+use crate::roux::{solve, CMLL};
+
+/// Whether the stickers at `positions` on `face` are as on the solved cube.
+fn stickers_solved(cube: &Cube, face: FaceName, positions: &[usize]) -> bool {
+    positions
+        .iter()
+        .all(|&p| cube[face].0[p] == Cube::SOLVED[face].colour())
+}
+
+fn blocks_solved(cube: &Cube) -> bool {
+    let sides = [3, 4, 5, 6, 7, 8];
+    stickers_solved(cube, Left, &sides)
+        && stickers_solved(cube, Right, &sides)
+        && stickers_solved(cube, Front, &[3, 5, 6, 8])
+        && stickers_solved(cube, Back, &[3, 5, 6, 8])
+        && stickers_solved(cube, Bottom, &[0, 2, 3, 5, 6, 8])
+}
+
+/// Whether the top corners are solved relative to each other.
+fn corners_solved(cube: &Cube) -> bool {
+    stickers_solved(cube, Top, &[0, 2, 6, 8])
+        && [Left, Front, Right, Back]
+            .iter()
+            .all(|&face| cube[face].0[0] == cube[face].0[2])
+}
+
+/// Whether the last six edges are oriented, with their top or bottom colour on
+/// the top or bottom face, and the M slice centres are as solved or turned
+/// by a half turn.
+fn l6e_oriented(cube: &Cube) -> bool {
+    let top_bottom = [Cube::SOLVED[Top].colour(), Cube::SOLVED[Bottom].colour()];
+    [1, 3, 4, 5, 7]
+        .iter()
+        .all(|&p| top_bottom.contains(&cube[Top].0[p]))
+        && [1, 7]
+            .iter()
+            .all(|&p| top_bottom.contains(&cube[Bottom].0[p]))
+}
+
+/// Every position of the last six edges with the centres solved and the top
+/// corners in place, found by a breadth first search over `<M, U>`.
+fn l6e_positions() -> Vec<Cube> {
+    let stickers = |cube: &Cube| -> Vec<Colour> { cube.faces.iter().flat_map(|f| f.0).collect() };
+    let mut seen = BTreeSet::from([stickers(&Cube::SOLVED)]);
+    let mut queue = VecDeque::from([Cube::SOLVED]);
+    let mut positions = Vec::new();
+    while let Some(cube) = queue.pop_front() {
+        for m in [Move::M, Move::U] {
+            let mut next = cube.clone();
+            next.make_move(m);
+            if seen.insert(stickers(&next)) {
+                queue.push_back(next);
+            }
+        }
+        if stickers_solved(&cube, Top, &[0, 2, 4]) && stickers_solved(&cube, Left, &[0, 2]) {
+            positions.push(cube);
+        }
+    }
+    positions
+}
+
+#[test]
+fn test_every_corner_case() {
+    let algs: Vec<Vec<Move>> = CMLL
+        .iter()
+        .map(|(_, alg)| crate::notation::parse(alg).unwrap())
+        .collect();
+    let u_corners = [CORNERS[0], CORNERS[1], CORNERS[2], CORNERS[3]];
+    for perm in 0..24 {
+        for twist in 0..27 {
+            let mut cube = CubieCube::SOLVED;
+            let mut available = u_corners.to_vec();
+            for (i, radix) in [6, 2, 1].into_iter().enumerate() {
+                cube.cp[i] = available.remove(perm / radix % (4 - i));
+            }
+            cube.cp[3] = available[0];
+            cube.co[..3].copy_from_slice(&[twist / 9, twist / 3 % 3, twist % 3]);
+            cube.co[3] = (6 - cube.co[..3].iter().sum::<u8>()) % 3;
+            if cube.validate().is_err() {
+                // Swapping two top edges evens out the parity.
+                cube.ep.swap(1, 3);
+            }
+
+            let cube = cube.to_cube();
+            let stages = solve(&cube);
+            assert!(stages[0].moves.is_empty() && stages[1].moves.is_empty());
+            // One look: a turn of the top layer at most, then an algorithm.
+            let cmll = &stages[2].moves;
+            assert!(
+                cmll.is_empty()
+                    || algs
+                        .iter()
+                        .any(|alg| cmll.ends_with(alg) && cmll.len() <= alg.len() + 1),
+                "{:?} isn't one look",
+                cmll
+            );
+            let mut solved = cube.clone();
+            for &m in &stages[2].moves {
+                solved.make_move(m);
+            }
+            assert!(corners_solved(&solved), "corners not solved:\n{}", cube);
+            assert!(blocks_solved(&solved), "blocks broken:\n{}", cube);
+        }
+    }
+}
+
+#[test]
+fn test_cmll_algorithms() {
+    for (name, alg) in CMLL {
+        let cube = scrambled(&crate::notation::parse(alg).unwrap());
+        assert!(blocks_solved(&cube), "{} breaks the blocks", name);
+        assert!(!corners_solved(&cube), "{} leaves the corners solved", name);
+    }
+}
+
+#[test]
+fn test_every_l6e_case() {
+    let positions = l6e_positions();
+    assert!(positions.len() > 1000);
+    for cube in positions {
+        let stages = solve(&cube);
+        assert!(stages[..3].iter().all(|stage| stage.moves.is_empty()));
+
+        let mut solved = cube.clone();
+        let mut make_stage = |stage: &Stage| {
+            for &m in &stage.moves {
+                solved.make_move(m);
+            }
+            solved.clone()
+        };
+        let eo = make_stage(&stages[3]);
+        assert!(l6e_oriented(&eo), "EO not solved:\n{}", cube);
+        let ulur = make_stage(&stages[4]);
+        assert!(l6e_oriented(&ulur), "EO broken:\n{}", cube);
+        assert!(
+            stickers_solved(&ulur, Left, &[0, 1, 2]) && stickers_solved(&ulur, Right, &[0, 1, 2]),
+            "UL and UR not solved:\n{}",
+            cube
+        );
+        let ep = make_stage(&stages[5]);
+        assert!(ep.faces == Cube::SOLVED.faces, "not solved:\n{}", cube);
+    }
+}
+
+#[test]
+fn test_solve() {
+    #[rustfmt::skip]
+    let sb_moves = [
+        Move::U, Move::U2, Move::UP, Move::R, Move::R2, Move::RP,
+        Move::M, Move::M2, Move::MP, Move::Rw, Move::Rw2, Move::RwP,
+    ];
+    let l6e_moves = [Move::U, Move::U2, Move::UP, Move::M, Move::M2, Move::MP];
+
+    for cube in scrambles().take(3) {
+        let stages = solve(&cube);
+        let names: Vec<_> = stages.iter().map(|stage| stage.name).collect();
+        assert_eq!(
+            names,
+            ["First block", "Second block", "CMLL", "EO", "UL/UR", "EP"]
+        );
+        assert!(stages[1].moves.iter().all(|m| sb_moves.contains(m)));
+        for stage in &stages[3..] {
+            assert!(stage.moves.iter().all(|m| l6e_moves.contains(m)));
+        }
+
+        let moves: Vec<Move> = stages.into_iter().flat_map(|stage| stage.moves).collect();
+        assert_solves(cube, &moves);
+    }
+}
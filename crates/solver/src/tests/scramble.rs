@@ -0,0 +1,133 @@
+use super::*;
+use crate::notation::parse;
+use crate::scramble::{
+    random_cube, random_moves, random_moves_with, random_state, too_short, Rng, ALL_FACES,
+    MIN_LENGTH,
+};
+
+#[test]
+fn test_rng() {
+    let numbers = |seed| {
+        let mut rng = Rng::new(seed);
+        (0..10).map(|_| rng.next_u64()).collect::<Vec<_>>()
+    };
+    assert_eq!(numbers(1), numbers(1));
+    assert_ne!(numbers(1), numbers(2));
+
+    let mut rng = Rng::new(7);
+    let mut counts = [0; 6];
+    for _ in 0..6000 {
+        counts[rng.below(6)] += 1;
+    }
+    assert!(counts.iter().all(|&count| (800..1200).contains(&count)));
+
+    let mut items: Vec<usize> = (0..20).collect();
+    rng.shuffle(&mut items);
+    assert_ne!(items, (0..20).collect::<Vec<_>>());
+    items.sort();
+    assert_eq!(items, (0..20).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_random_cube() {
+    let mut rng = Rng::new(42);
+    let cubes: Vec<_> = (0..200).map(|_| random_cube(&mut rng)).collect();
+    assert!(cubes.iter().all(|cube| cube.validate().is_ok()));
+    // Both parities turn up, as do corners away from home.
+    let odd = cubes
+        .iter()
+        .filter(|cube| crate::cubie::parity(&cube.cp.map(|c| c as u8)))
+        .count();
+    assert!((60..140).contains(&odd));
+    assert!(cubes.iter().any(|cube| cube.co[7] != 0 && cube.eo[11] != 0));
+}
+
+#[test]
+fn test_too_short() {
+    use crate::cubie::CubieCube;
+    let cube = |moves: &[Move]| CubieCube::from_cube(&scrambled(moves)).unwrap();
+    assert!(too_short(&CubieCube::SOLVED));
+    assert!(too_short(&cube(&[Move::R])));
+    assert!(too_short(&cube(&parse("R U F' D2").unwrap())));
+    assert!(!too_short(&cube(&parse("R U F' D2 L").unwrap())));
+}
+
+#[test]
+fn test_random_state() {
+    let scrambles: Vec<String> = (0..2).map(random_state).collect();
+    assert_eq!(random_state(0), scrambles[0]);
+    assert_ne!(scrambles[0], scrambles[1]);
+    for (seed, scramble) in scrambles.iter().enumerate() {
+        let moves = parse(scramble).unwrap();
+        assert!((MIN_LENGTH..=21).contains(&moves.len()));
+        // The scramble makes the state the seed's generator draws first.
+        let cube = random_cube(&mut Rng::new(seed as u64)).to_cube();
+        assert!(scrambled(&moves).faces == cube.faces);
+    }
+}
+
+/// Checks that no face is turned twice in a row, and no face turned again
+/// after its opposite.
+fn assert_no_cancellations(moves: &[Move]) {
+    let faces: Vec<char> = moves.iter().map(|m| m.to_string().remove(0)).collect();
+    let opposite = |a, b| ["UD", "DU", "RL", "LR", "FB", "BF"].contains(&&*format!("{}{}", a, b));
+    for (i, &face) in faces.iter().enumerate().skip(1) {
+        assert_ne!(face, faces[i - 1], "{:?}", moves);
+        if i > 1 && opposite(face, faces[i - 1]) {
+            assert_ne!(face, faces[i - 2], "{:?}", moves);
+        }
+    }
+}
+
+#[test]
+fn test_random_moves() {
+    let scramble = random_moves(3, 25, &ALL_FACES);
+    assert_eq!(scramble, random_moves(3, 25, &ALL_FACES));
+    assert_ne!(scramble, random_moves(4, 25, &ALL_FACES));
+    let moves = parse(&scramble).unwrap();
+    assert_eq!(moves.len(), 25);
+
+    let mut rng = Rng::new(5);
+    let moves = random_moves_with(&mut rng, 1000, &ALL_FACES);
+    assert_no_cancellations(&moves);
+    let turns = parse("U U' U2 R R' R2 F F' F2 D D' D2 L L' L2 B B' B2").unwrap();
+    assert!(turns.iter().all(|m| moves.contains(m)));
+}
+
+#[test]
+fn test_restricted_moves() {
+    let mut rng = Rng::new(6);
+    let moves = random_moves_with(&mut rng, 1000, &[Move::R, Move::U]);
+    assert_no_cancellations(&moves);
+    let ru = [Move::R, Move::RP, Move::R2, Move::U, Move::UP, Move::U2];
+    assert!(moves.iter().all(|m| ru.contains(m)));
+    // Half turn generators only give half turns of their face.
+    let moves = random_moves_with(&mut rng, 1000, &[Move::R2, Move::U]);
+    assert_no_cancellations(&moves);
+    assert!(!moves.contains(&Move::R) && !moves.contains(&Move::RP));
+    assert!([Move::R2, Move::U, Move::UP, Move::U2]
+        .iter()
+        .all(|m| moves.contains(m)));
+    let moves = random_moves_with(&mut rng, 1000, &[Move::U, Move::R2, Move::F2]);
+    let ur2f2 = [Move::U, Move::UP, Move::U2, Move::R2, Move::F2];
+    assert!(moves.iter().all(|m| ur2f2.contains(m)));
+    // Opposite faces can only be turned with some other face between them.
+    let moves = random_moves_with(&mut rng, 1000, &[Move::R, Move::L, Move::U]);
+    assert_no_cancellations(&moves);
+}
+
+#[test]
+#[should_panic(expected = "generators must turn faces on at least two axes")]
+fn test_one_axis() {
+    random_moves(0, 10, &[Move::R, Move::LP]);
+}
+
+#[test]
+fn test_solve_random_moves() {
+    let mut rng = Rng::new(8);
+    for _ in 0..10 {
+        let moves = random_moves_with(&mut rng, 25, &ALL_FACES);
+        let cube = scrambled(&moves);
+        assert_solves(cube.clone(), &solve(cube));
+    }
+}
@@ -0,0 +1,52 @@
+use super::*;
+use crate::cubie::CubieCube;
+
+/// Whether the moves only use half turns and the given faces' quarter turns.
+fn uses_quarter_turns_of(moves: &[Move], faces: &[Move]) -> bool {
+    moves
+        .iter()
+        .all(|&m| HALF_TURNS.contains(&m) || faces.iter().any(|&f| f == m || f.reverse() == m))
+}
+
+#[test]
+fn test_solve_solved() {
+    let stages = crate::thistlethwaite::solve(&Cube::SOLVED);
+    assert_eq!(stages.len(), 4);
+    assert!(stages.iter().all(|stage| stage.moves.is_empty()));
+}
+
+#[test]
+fn test_solve() {
+    for cube in scrambles().take(10) {
+        let stages = crate::thistlethwaite::solve(&cube);
+        let moves: Vec<Move> = stages.iter().flat_map(|s| s.moves.clone()).collect();
+        assert_solves(cube, &moves);
+    }
+}
+
+#[test]
+fn test_phases_stay_in_group() {
+    use Move::*;
+    let g1 = [U, D, L, R];
+    let g2 = [U, D];
+    for cube in scrambles().take(10) {
+        let stages = crate::thistlethwaite::solve(&cube);
+        assert!(uses_quarter_turns_of(&stages[1].moves, &g1));
+        assert!(uses_quarter_turns_of(&stages[2].moves, &g2));
+        assert!(uses_quarter_turns_of(&stages[3].moves, &[]));
+
+        // After the first phase every edge is oriented, and after the second
+        // every corner is too, with the middle layer edges in place.
+        let mut cube = cube;
+        for &m in &stages[0].moves {
+            cube.make_move(m);
+        }
+        let cubie = CubieCube::from_cube(&cube).unwrap();
+        assert_eq!(cubie.flip(), 0);
+        for &m in &stages[1].moves {
+            cube.make_move(m);
+        }
+        let cubie = CubieCube::from_cube(&cube).unwrap();
+        assert_eq!((cubie.flip(), cubie.twist(), cubie.slice()), (0, 0, 0));
+    }
+}
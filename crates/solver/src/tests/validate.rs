@@ -0,0 +1,128 @@
+use super::*;
+use crate::cubie::{self, CubieCube};
+
+/// The solved cube with some of its stickers repainted.
+fn repainted(stickers: &[(FaceName, usize, Colour)]) -> Cube {
+    let mut cube = Cube::SOLVED;
+    for &(face, idx, colour) in stickers {
+        cube[face].0[idx] = colour;
+    }
+    cube
+}
+
+#[test]
+fn test_valid() {
+    assert_eq!(Cube::SOLVED.validate(), Ok(()));
+    for cube in scrambles() {
+        assert_eq!(cube.validate(), Ok(()));
+    }
+}
+
+#[test]
+fn test_colour_count() {
+    let cube = repainted(&[(Top, 0, White)]);
+    assert_eq!(
+        cube.validate(),
+        Err(CubeError::ColourCount {
+            colour: White,
+            count: 10
+        })
+    );
+}
+
+#[test]
+fn test_duplicate_centre() {
+    let cube = repainted(&[(Top, 0, Blue), (Front, 4, Yellow)]);
+    assert_eq!(
+        cube.validate(),
+        Err(CubeError::DuplicateCentre { colour: Yellow })
+    );
+}
+
+#[test]
+fn test_impossible_pieces() {
+    let cube = repainted(&[(Front, 2, Orange), (Left, 2, Blue)]);
+    let err = CubeError::ImpossibleCorner {
+        position: cubie::Corner::Urf,
+        colours: [Yellow, Red, Orange],
+    };
+    assert_eq!(cube.validate(), Err(err));
+    assert_eq!(
+        err.to_string(),
+        "the corner at URF has colours Y, R and O, which no corner has"
+    );
+
+    let cube = repainted(&[(Front, 1, White), (Bottom, 1, Blue)]);
+    assert_eq!(
+        cube.validate(),
+        Err(CubeError::ImpossibleEdge {
+            position: cubie::Edge::Uf,
+            colours: [Yellow, White],
+        })
+    );
+}
+
+#[test]
+fn test_duplicate_pieces() {
+    let mut cube = CubieCube::SOLVED;
+    cube.cp[1] = cubie::Corner::Urf;
+    assert_eq!(
+        cube.validate(),
+        Err(CubeError::DuplicateCorner {
+            corner: cubie::Corner::Urf,
+            positions: [cubie::Corner::Urf, cubie::Corner::Ufl],
+        })
+    );
+
+    let mut cube = CubieCube::SOLVED;
+    cube.ep[11] = cubie::Edge::Ub;
+    assert_eq!(
+        cube.validate(),
+        Err(CubeError::DuplicateEdge {
+            edge: cubie::Edge::Ub,
+            positions: [cubie::Edge::Ub, cubie::Edge::Br],
+        })
+    );
+}
+
+#[test]
+fn test_parity() {
+    // Twist the top front right corner in place.
+    let cube = repainted(&[(Top, 8, Blue), (Right, 0, Yellow), (Front, 2, Red)]);
+    assert_eq!(cube.validate(), Err(CubeError::CornerTwist { twist: 1 }));
+
+    // Flip the top front edge in place.
+    let cube = repainted(&[(Top, 7, Blue), (Front, 1, Yellow)]);
+    assert_eq!(cube.validate(), Err(CubeError::EdgeFlip));
+
+    // Swap the top front and top back edges.
+    let cube = repainted(&[(Front, 1, Green), (Back, 1, Blue)]);
+    assert_eq!(cube.validate(), Err(CubeError::PermutationParity));
+}
+
+#[test]
+fn test_try_make_cube() {
+    let mut colours = [White; 54];
+    assert_eq!(
+        Cube::try_make_cube(colours).unwrap_err(),
+        CubeError::ColourCount {
+            colour: White,
+            count: 54
+        }
+    );
+
+    for (i, colour) in colours.iter_mut().enumerate() {
+        *colour = [Yellow, Orange, Blue, Red, Green, White][match i {
+            0..=8 => 0,
+            45..=53 => 5,
+            _ => (i - 9) % 12 / 3 + 1,
+        }];
+    }
+    assert!(Cube::try_make_cube(colours).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "an odd number of edges are flipped")]
+fn test_solve_invalid() {
+    solve(repainted(&[(Top, 7, Blue), (Front, 1, Yellow)]));
+}
@@ -0,0 +1,82 @@
+use super::*;
+use crate::cubie::{CubieCube, Edge, N_FLIP};
+use crate::metric::Metric;
+use crate::zz::{solve, solve_eoline};
+
+#[test]
+fn test_misoriented_edges() {
+    assert_eq!(Cube::SOLVED.misoriented_edges(), Ok(vec![]));
+    assert_eq!(
+        scrambled(&[Move::R, Move::U2, Move::L]).misoriented_edges(),
+        Ok(vec![])
+    );
+    assert_eq!(
+        scrambled(&[Move::F]).misoriented_edges(),
+        Ok(vec![Edge::Uf, Edge::Df, Edge::Fr, Edge::Fl])
+    );
+    // Half turns of F don't flip anything.
+    assert_eq!(
+        scrambled(&[Move::F2, Move::B2]).misoriented_edges(),
+        Ok(vec![])
+    );
+
+    let mut cube = Cube::SOLVED;
+    cube.faces[0].0[1] = Colour::White;
+    assert!(cube.misoriented_edges().is_err());
+}
+
+#[test]
+fn test_every_eoline_case() {
+    for df in 0..12 {
+        for db in (0..12).filter(|&db| db != df) {
+            let mut placed = CubieCube::SOLVED;
+            placed.ep.swap(Edge::Df as usize, df);
+            let from = placed.ep.iter().position(|&e| e == Edge::Db).unwrap();
+            placed.ep.swap(from, db);
+            if placed.validate().is_err() {
+                // Swapping two corners evens out the parity.
+                placed.cp.swap(0, 1);
+            }
+            for flip in 0..N_FLIP {
+                let mut cube = placed.clone();
+                cube.set_flip(flip);
+                let moves = solve_eoline(&mut cube, Metric::Htm);
+                // Every EOLine can be solved in at most 9 moves.
+                assert!(moves.len() <= 9, "{} moves for {:?}", moves.len(), placed);
+                assert_eq!(cube.eo, [0; 12]);
+                assert_eq!(cube.ep[Edge::Df as usize], Edge::Df);
+                assert_eq!(cube.ep[Edge::Db as usize], Edge::Db);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_solve() {
+    let lur = [
+        Move::L,
+        Move::LP,
+        Move::L2,
+        Move::U,
+        Move::UP,
+        Move::U2,
+        Move::R,
+        Move::RP,
+        Move::R2,
+    ];
+    for cube in scrambles().take(3) {
+        let stages = solve(&cube);
+        let names: Vec<_> = stages.iter().map(|stage| stage.name).collect();
+        assert_eq!(names, ["EOLine", "F2L", "OLL", "PLL"]);
+
+        let mut eoline = cube.clone();
+        for &m in &stages[0].moves {
+            eoline.make_move(m);
+        }
+        assert_eq!(eoline.misoriented_edges(), Ok(vec![]));
+        assert!(stages[1].moves.iter().all(|m| lur.contains(m)));
+
+        let moves: Vec<Move> = stages.into_iter().flat_map(|stage| stage.moves).collect();
+        assert_solves(cube, &moves);
+    }
+}
@@ -0,0 +1,303 @@
+//! Thistlethwaite's four-phase algorithm.
+//!
+//! The cube is moved through a chain of nested subgroups, each generated by
+//! fewer kinds of moves than the last:
+//!
+//! - G0 = <U, D, L, R, F, B>, every cube.
+//! - G1 = <U, D, L, R, F2, B2>, where every edge is oriented.
+//! - G2 = <U, D, L2, R2, F2, B2>, where every corner is also oriented and the
+//!   middle layer edges are in the middle layer.
+//! - G3 = <U2, D2, L2, R2, F2, B2>, where every piece is in a position it can
+//!   reach with half turns alone.
+//! - G4, the solved cube.
+//!
+//! Each phase takes the cube from one group to the next using only the moves
+//! of the first. Its lookup table holds the exact cost of the moves needed, in
+//! the metric being solved in, from every state of the coordinates that matter
+//! for that phase, so each phase is solved optimally by always making a move
+//! that brings that cost down by what the move costs.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::cubie::{
+    face_turn_move, CubieCube, Edge, N_CORNER_PERM, N_FLIP, N_MOVES, N_SLICE, N_SLICE_PERM,
+    N_TWIST, N_UD_EDGE_PERM,
+};
+use crate::metric::Metric;
+use crate::search::{
+    descend, distance_table, face_turn_costs, face_turn_metric, make_face_turns, move_table,
+    PerMetric, ALL_MOVES,
+};
+use crate::{Cube, Stage};
+
+/// The face turns allowed in each phase, the generators of G0 to G3.
+const G0_MOVES: [usize; 18] = ALL_MOVES;
+const G1_MOVES: [usize; 14] = [0, 1, 2, 3, 4, 5, 7, 9, 10, 11, 12, 13, 14, 16];
+const G2_MOVES: [usize; 10] = [0, 1, 2, 4, 7, 9, 10, 11, 13, 16];
+const G3_MOVES: [usize; 6] = [1, 4, 7, 10, 13, 16];
+
+/// The group each phase brings the cube into.
+const STAGE_NAMES: [&str; 4] = [
+    "G1 <U, D, L, R, F2, B2>",
+    "G2 <U, D, L2, R2, F2, B2>",
+    "G3 <U2, D2, L2, R2, F2, B2>",
+    "G4 solved",
+];
+
+/// The edges of the slice between the left and right faces.
+const M_SLICE_EDGES: [Edge; 4] = [Edge::Uf, Edge::Ub, Edge::Df, Edge::Db];
+const OTHER_UD_EDGES: [Edge; 4] = [Edge::Ur, Edge::Ul, Edge::Dr, Edge::Dl];
+
+/// The number of ways to place the four M slice edges among the eight top and
+/// bottom layer edge positions.
+const N_M_SLICE: usize = 70;
+
+/// Marks lookup table entries that haven't been reached yet.
+const UNKNOWN: u8 = u8::MAX;
+
+struct Tables {
+    flip_move: Vec<[u16; N_MOVES]>,
+    twist_move: Vec<[u16; N_MOVES]>,
+    slice_move: Vec<[u16; N_MOVES]>,
+    corner_perm_move: Vec<[u16; N_MOVES]>,
+    m_slice_move: Vec<[u16; N_MOVES]>,
+    ud_edge_perm_move: Vec<[u16; N_MOVES]>,
+    slice_perm_move: Vec<[u16; N_MOVES]>,
+
+    /// Moves to solved, by corner, top and bottom edge, and slice edge
+    /// permutation. Only the states in G3 are included. Every move of G3 is a
+    /// half turn, so all cost the same in any metric and the fewest moves are
+    /// also the cheapest.
+    phase4: HashMap<(u16, u16, u8), u8>,
+}
+
+/// The cost of the moves to each group, in a metric.
+struct Distances {
+    /// To G1, by flip.
+    phase1: Vec<u8>,
+    /// To G2, by twist and slice.
+    phase2: Vec<u8>,
+    /// To G3, by corner permutation and M slice.
+    phase3: Vec<u8>,
+}
+
+/// The masks of which top and bottom layer edge positions hold M slice edges,
+/// indexed by the M slice coordinate.
+fn m_slice_masks() -> Vec<u8> {
+    (0..=u8::MAX)
+        .filter(|mask| mask.count_ones() == 4)
+        .collect()
+}
+
+fn m_slice(cube: &CubieCube, masks: &[u8]) -> usize {
+    let mask = cube.ep[..8]
+        .iter()
+        .enumerate()
+        .filter(|(_, edge)| M_SLICE_EDGES.contains(edge))
+        .fold(0, |acc, (pos, _)| acc | 1 << pos);
+    masks.binary_search(&mask).unwrap()
+}
+
+fn set_m_slice(cube: &mut CubieCube, coord: usize, masks: &[u8]) {
+    let (mut m_edges, mut other_edges) = (M_SLICE_EDGES.iter(), OTHER_UD_EDGES.iter());
+    for pos in 0..8 {
+        cube.ep[pos] = if masks[coord] & 1 << pos != 0 {
+            *m_edges.next().unwrap()
+        } else {
+            *other_edges.next().unwrap()
+        };
+    }
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let masks = m_slice_masks();
+
+        let flip_move = move_table(N_FLIP, &G0_MOVES, CubieCube::set_flip, CubieCube::flip);
+        let twist_move = move_table(N_TWIST, &G1_MOVES, CubieCube::set_twist, CubieCube::twist);
+        let slice_move = move_table(N_SLICE, &G1_MOVES, CubieCube::set_slice, CubieCube::slice);
+        let corner_perm_move = move_table(
+            N_CORNER_PERM,
+            &G2_MOVES,
+            CubieCube::set_corner_perm,
+            CubieCube::corner_perm,
+        );
+        let m_slice_move = move_table(
+            N_M_SLICE,
+            &G2_MOVES,
+            |cube, coord| set_m_slice(cube, coord, &masks),
+            |cube| m_slice(cube, &masks),
+        );
+        let ud_edge_perm_move = move_table(
+            N_UD_EDGE_PERM,
+            &G3_MOVES,
+            CubieCube::set_ud_edge_perm,
+            CubieCube::ud_edge_perm,
+        );
+        let slice_perm_move = move_table(
+            N_SLICE_PERM,
+            &G3_MOVES,
+            CubieCube::set_slice_perm,
+            CubieCube::slice_perm,
+        );
+
+        let mut phase4 = HashMap::from([((0, 0, 0), 0)]);
+        let mut frontier = vec![(0, 0, 0)];
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            let mut next = Vec::new();
+            for (cp, ep, sp) in frontier {
+                for m in G3_MOVES {
+                    let moved = (
+                        corner_perm_move[cp as usize][m],
+                        ud_edge_perm_move[ep as usize][m],
+                        slice_perm_move[sp as usize][m] as u8,
+                    );
+                    phase4.entry(moved).or_insert_with(|| {
+                        next.push(moved);
+                        depth + 1
+                    });
+                }
+            }
+            frontier = next;
+            depth += 1;
+        }
+
+        Tables {
+            flip_move,
+            twist_move,
+            slice_move,
+            corner_perm_move,
+            m_slice_move,
+            ud_edge_perm_move,
+            slice_perm_move,
+            phase4,
+        }
+    })
+}
+
+fn distances(metric: Metric) -> &'static Distances {
+    static DISTANCES: PerMetric<Distances> = PerMetric::new();
+    let metric = face_turn_metric(metric);
+    DISTANCES.get_or_init(metric, || {
+        let t = tables();
+        let costs = face_turn_costs(metric);
+        let phase1 = distance_table(N_FLIP, &[0], &G0_MOVES, &costs, |flip, m| {
+            t.flip_move[flip][m] as usize
+        });
+        let phase2 = distance_table(N_TWIST * N_SLICE, &[0], &G1_MOVES, &costs, |index, m| {
+            let (twist, slice) = (index / N_SLICE, index % N_SLICE);
+            t.twist_move[twist][m] as usize * N_SLICE + t.slice_move[slice][m] as usize
+        });
+
+        // G3 allows 96 of the corner permutations, the ones half turns can
+        // reach, with the M slice edges in the M slice.
+        let half_turn_corners = distance_table(N_CORNER_PERM, &[0], &G3_MOVES, &costs, |cp, m| {
+            t.corner_perm_move[cp][m] as usize
+        });
+        let solved_m_slice = m_slice(&CubieCube::SOLVED, &m_slice_masks());
+        let goals: Vec<usize> = (0..N_CORNER_PERM)
+            .filter(|&cp| half_turn_corners[cp] != UNKNOWN)
+            .map(|cp| cp * N_M_SLICE + solved_m_slice)
+            .collect();
+        let phase3 = distance_table(
+            N_CORNER_PERM * N_M_SLICE,
+            &goals,
+            &G2_MOVES,
+            &costs,
+            |index, m| {
+                let (cp, m_slice) = (index / N_M_SLICE, index % N_M_SLICE);
+                t.corner_perm_move[cp][m] as usize * N_M_SLICE + t.m_slice_move[m_slice][m] as usize
+            },
+        );
+        Distances {
+            phase1,
+            phase2,
+            phase3,
+        }
+    })
+}
+
+/// Solves the cube, giving the moves of each phase separately.
+///
+/// Panics if the cube can't be solved.
+pub fn solve(cube: &Cube) -> Vec<Stage> {
+    solve_in(cube, Metric::Htm)
+}
+
+/// Solves the cube like [`solve`], with each phase as short as it can be in
+/// `metric`.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_in(cube: &Cube, metric: Metric) -> Vec<Stage> {
+    let mut cube = CubieCube::from_solvable_cube(cube);
+    let (t, d) = (tables(), distances(metric));
+    let costs = face_turn_costs(metric);
+    let masks = m_slice_masks();
+
+    let phase1 = descend(
+        cube.flip(),
+        &G0_MOVES,
+        &costs,
+        |flip, m| t.flip_move[flip][m] as usize,
+        |flip| d.phase1[flip],
+    );
+    make_face_turns(&mut cube, &phase1);
+
+    let phase2 = descend(
+        (cube.twist(), cube.slice()),
+        &G1_MOVES,
+        &costs,
+        |(twist, slice), m| {
+            (
+                t.twist_move[twist][m] as usize,
+                t.slice_move[slice][m] as usize,
+            )
+        },
+        |(twist, slice)| d.phase2[twist * N_SLICE + slice],
+    );
+    make_face_turns(&mut cube, &phase2);
+
+    let phase3 = descend(
+        (cube.corner_perm(), m_slice(&cube, &masks)),
+        &G2_MOVES,
+        &costs,
+        |(cp, m_slice), m| {
+            (
+                t.corner_perm_move[cp][m] as usize,
+                t.m_slice_move[m_slice][m] as usize,
+            )
+        },
+        |(cp, m_slice)| d.phase3[cp * N_M_SLICE + m_slice],
+    );
+    make_face_turns(&mut cube, &phase3);
+
+    let phase4 = descend(
+        (
+            cube.corner_perm() as u16,
+            cube.ud_edge_perm() as u16,
+            cube.slice_perm() as u8,
+        ),
+        &G3_MOVES,
+        &[1; N_MOVES],
+        |(cp, ep, sp), m| {
+            (
+                t.corner_perm_move[cp as usize][m],
+                t.ud_edge_perm_move[ep as usize][m],
+                t.slice_perm_move[sp as usize][m] as u8,
+            )
+        },
+        |state| t.phase4[&state],
+    );
+
+    [phase1, phase2, phase3, phase4]
+        .into_iter()
+        .zip(STAGE_NAMES)
+        .map(|(phase, name)| Stage {
+            name,
+            moves: phase.iter().map(|&m| face_turn_move(m)).collect(),
+        })
+        .collect()
+}
@@ -0,0 +1,172 @@
+//! The ZZ method: EOLine, then the first two layers with only `<L, U, R>`, then
+//! the last layer.
+//!
+//! EOLine orients every edge relative to the front and back faces and solves
+//! the DF and DB edges. Quarter turns of F and B are then never needed, so the
+//! first two layers are built as a 1x2x3 block either side of the line using
+//! only L, U and R. With the edges oriented, the last layer is one of the
+//! corner orientation cases of OLL followed by PLL.
+
+use std::sync::OnceLock;
+
+use crate::cfop::{solve_oll, solve_pll};
+use crate::cubie::{face_turn_move, CubieCube, Edge, N_FLIP, N_MOVES};
+use crate::metric::Metric;
+use crate::search::{
+    database, descend, distance_table, face_location_move, face_turn_costs,
+    face_turn_location_move, face_turn_metric, ida_star, make_face_turns, move_table, Location,
+    PerMetric, State, ALL_MOVES, N_LOCATIONS,
+};
+use crate::{Cube, Stage};
+
+/// The face turns of U, R and L.
+const LUR_MOVES: [usize; 9] = [0, 1, 2, 3, 4, 5, 12, 13, 14];
+
+/// The pieces of the left block then the right block, as indices into the
+/// corners followed by the edges of the pattern databases, each block split
+/// into two groups that each have a database.
+const BLOCK_GROUPS: [[usize; 4]; 4] = [
+    [5, 6, 14, 17],
+    [5, 6, 14, 18],
+    [4, 7, 12, 16],
+    [4, 7, 12, 19],
+];
+
+const STAGE_NAMES: [&str; 4] = ["EOLine", "F2L", "OLL", "PLL"];
+
+struct Tables {
+    flip_move: Vec<[u16; N_MOVES]>,
+    lur_location_move: [[[Location; 9]; N_LOCATIONS]; 2],
+    block_databases: [Vec<u8>; 4],
+}
+
+fn eoline_index(flip: usize, df: Location, db: Location) -> usize {
+    (flip * N_LOCATIONS + df as usize) * N_LOCATIONS + db as usize
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(|| {
+        let lur_location_move = face_turn_location_move(&LUR_MOVES);
+        Tables {
+            flip_move: move_table(N_FLIP, &ALL_MOVES, CubieCube::set_flip, CubieCube::flip),
+            block_databases: BLOCK_GROUPS.map(|group| database(&lur_location_move, &group)),
+            lur_location_move,
+        }
+    })
+}
+
+/// The cost of the moves to EOLine in a metric, by flip and the locations of
+/// the DF and DB edges.
+fn eoline_distances(metric: Metric) -> &'static Vec<u8> {
+    static EOLINE: PerMetric<Vec<u8>> = PerMetric::new();
+    let metric = face_turn_metric(metric);
+    EOLINE.get_or_init(metric, || {
+        let (t, edge_move) = (tables(), &face_location_move()[1]);
+        let solved = eoline_index(0, Edge::Df as Location * 2, Edge::Db as Location * 2);
+        distance_table(
+            N_FLIP * N_LOCATIONS * N_LOCATIONS,
+            &[solved],
+            &ALL_MOVES,
+            &face_turn_costs(metric),
+            |index, m| {
+                let (flip, df, db) = (
+                    index / N_LOCATIONS / N_LOCATIONS,
+                    index / N_LOCATIONS % N_LOCATIONS,
+                    index % N_LOCATIONS,
+                );
+                eoline_index(
+                    t.flip_move[flip][m] as usize,
+                    edge_move[df][m],
+                    edge_move[db][m],
+                )
+            },
+        )
+    })
+}
+
+/// Searches `<L, U, R>` for a cheapest way in `metric` to solve the first
+/// `groups` of [`BLOCK_GROUPS`].
+fn solve_blocks(cube: &CubieCube, groups: usize, metric: Metric) -> Vec<usize> {
+    let t = tables();
+    // L and R turn about the same axis, so are only searched in that order.
+    let redundant = |prev: usize, m: usize| {
+        let (prev_face, face) = (prev / 3, m / 3);
+        prev_face == face || (prev_face == 2 && face == 1)
+    };
+    let bound = |state: &State| {
+        BLOCK_GROUPS[..groups]
+            .iter()
+            .zip(&t.block_databases)
+            .map(|(group, database)| database[state.group_index(group)])
+            .max()
+            .unwrap()
+    };
+    let costs = face_turn_costs(metric);
+    ida_star(
+        State::from_cubie(cube),
+        &t.lur_location_move,
+        &LUR_MOVES.map(|m| costs[m]),
+        redundant,
+        bound,
+    )
+    .into_iter()
+    .map(|m| LUR_MOVES[m])
+    .collect()
+}
+
+/// Orients every edge and solves the DF and DB edges as cheaply as possible in
+/// `metric`, applying the moves to `cube`.
+pub(crate) fn solve_eoline(cube: &mut CubieCube, metric: Metric) -> Vec<usize> {
+    let (t, eoline) = (tables(), eoline_distances(metric));
+    let edge_move = &face_location_move()[1];
+    let state = State::from_cubie(cube);
+    let eoline = descend(
+        (
+            cube.flip(),
+            state.location(8 + Edge::Df as usize),
+            state.location(8 + Edge::Db as usize),
+        ),
+        &ALL_MOVES,
+        &face_turn_costs(metric),
+        |(flip, df, db), m| {
+            (
+                t.flip_move[flip][m] as usize,
+                edge_move[df as usize][m],
+                edge_move[db as usize][m],
+            )
+        },
+        |(flip, df, db)| eoline[eoline_index(flip, df, db)],
+    );
+    make_face_turns(cube, &eoline);
+    eoline
+}
+
+/// Solves the cube with ZZ, giving the moves of each stage separately.
+///
+/// Panics if the cube can't be solved.
+pub fn solve(cube: &Cube) -> Vec<Stage> {
+    solve_in(cube, Metric::Htm)
+}
+
+/// Solves the cube like [`solve`], with the EOLine and each block as short as
+/// they can be in `metric`.
+///
+/// Panics if the cube can't be solved.
+pub fn solve_in(cube: &Cube, metric: Metric) -> Vec<Stage> {
+    let mut cube = CubieCube::from_solvable_cube(cube);
+    let eoline = solve_eoline(&mut cube, metric);
+    let left = solve_blocks(&cube, 2, metric);
+    make_face_turns(&mut cube, &left);
+    let right = solve_blocks(&cube, 4, metric);
+    make_face_turns(&mut cube, &right);
+
+    let to_moves = |path: Vec<usize>| path.into_iter().map(face_turn_move).collect();
+    let oll = solve_oll(&mut cube);
+    let pll = solve_pll(&mut cube);
+    [to_moves(eoline), to_moves([left, right].concat()), oll, pll]
+        .into_iter()
+        .zip(STAGE_NAMES)
+        .map(|(moves, name)| Stage { name, moves })
+        .collect()
+}